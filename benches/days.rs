@@ -0,0 +1,105 @@
+use std::hint::black_box;
+use std::time::Duration;
+
+use advent_of_code_2022::solution::Solution;
+use advent_of_code_2022::{
+    day_1, day_10, day_11, day_12, day_13, day_14, day_15, day_16, day_18, day_2, day_3, day_4,
+    day_5, day_6, day_7, day_8, day_9,
+};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+
+fn read_input(path: &str) -> String {
+    std::fs::read_to_string(path).expect("Failed to read input")
+}
+
+// Parse and solve phases of a single day against its checked in input
+fn bench_solution<S: Solution>(c: &mut Criterion) {
+    let input = read_input(&S::input_path());
+    let parsed = S::parse(&input).expect("Failed to parse input");
+
+    let mut group = c.benchmark_group(format!("day_{}", S::DAY));
+    group.bench_function("parse", |b| b.iter(|| S::parse(black_box(&input))));
+    group.bench_function("part_1", |b| b.iter(|| S::part1(black_box(&parsed))));
+    if S::PARTS > 1 {
+        group.bench_function("part_2", |b| b.iter(|| S::part2(black_box(&parsed))));
+    }
+    group.finish();
+}
+
+fn bench_days(c: &mut Criterion) {
+    bench_solution::<day_1::Day1>(c);
+    bench_solution::<day_2::Day2>(c);
+    bench_solution::<day_3::Day3>(c);
+    bench_solution::<day_4::Day4>(c);
+    bench_solution::<day_5::Day5>(c);
+    bench_solution::<day_6::Day6>(c);
+    bench_solution::<day_7::Day7>(c);
+    bench_solution::<day_8::Day8>(c);
+    bench_solution::<day_9::Day9>(c);
+    bench_solution::<day_10::Day10>(c);
+    bench_solution::<day_11::Day11>(c);
+    bench_solution::<day_12::Day12>(c);
+    bench_solution::<day_13::Day13>(c);
+    bench_solution::<day_14::Day14>(c);
+    bench_solution::<day_18::Day18>(c);
+}
+
+// Day 15 part 2 takes over a second and day 16 part 1 tens of seconds on the real input, so
+// they get the minimum sample count
+fn bench_slow_days(c: &mut Criterion) {
+    bench_solution::<day_15::Day15>(c);
+    bench_solution::<day_16::Day16>(c);
+}
+
+fn bench_hot_paths(c: &mut Criterion) {
+    let code = read_input("input/day_6.txt");
+    c.bench_function("day_6/find_unique_seq_4", |b| {
+        b.iter(|| day_6::find_unique_seq(black_box(&code), 4))
+    });
+    c.bench_function("day_6/find_unique_seq_14", |b| {
+        b.iter(|| day_6::find_unique_seq(black_box(&code), 14))
+    });
+
+    let topo_map = day_12::Day12::parse(&read_input("input/day_12.txt")).unwrap();
+    c.bench_function("day_12/find_shortest_path", |b| {
+        b.iter(|| black_box(&topo_map).find_shortest_path(false))
+    });
+    c.bench_function("day_12/find_shortest_path_include_a", |b| {
+        b.iter(|| black_box(&topo_map).find_shortest_path(true))
+    });
+
+    let cave = day_14::Day14::parse(&read_input("input/day_14.txt")).unwrap();
+    c.bench_function("day_14/drop_sand", |b| {
+        b.iter_batched(
+            || cave.clone(),
+            |mut cave| cave.drop_sand(false),
+            BatchSize::LargeInput,
+        )
+    });
+    c.bench_function("day_14/drop_sand_floor", |b| {
+        b.iter_batched(
+            || cave.clone(),
+            |mut cave| cave.drop_sand(true),
+            BatchSize::LargeInput,
+        )
+    });
+
+    let pairs = day_15::Day15::parse(&read_input("input/day_15_test.txt")).unwrap();
+    c.bench_function("day_15/check_at_y", |b| {
+        b.iter(|| day_15::check_at_y(black_box(&pairs), 10))
+    });
+
+    let volcano = day_16::Day16::parse(&read_input("input/day_16_test.txt")).unwrap();
+    c.bench_function("day_16/find_highest_pressure", |b| {
+        b.iter(|| black_box(&volcano).find_highest_pressure(30))
+    });
+}
+
+criterion_group!(days, bench_days);
+criterion_group! {
+    name = slow_days;
+    config = Criterion::default().sample_size(10).measurement_time(Duration::from_secs(60));
+    targets = bench_slow_days
+}
+criterion_group!(hot_paths, bench_hot_paths);
+criterion_main!(days, slow_days, hot_paths);
@@ -0,0 +1,114 @@
+//! Typed puzzle answers.
+
+use std::fmt::Display;
+
+use serde::Serialize;
+
+/// A part's answer. Serializes as `{"kind": ..., "value": ...}` so a one line render can be
+/// told apart from a string, with both integer variants under the `int` kind.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum Answer {
+    /// Numeric answer.
+    Int(u64),
+    /// Numeric answer that may be negative.
+    #[serde(rename = "int")]
+    Signed(i64),
+    /// Text answer, like day 5's crate tops.
+    Str(String),
+    /// Multi-line output like day 10's CRT display, newline terminated
+    Render(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(val) => write!(f, "{}", val),
+            Answer::Signed(val) => write!(f, "{}", val),
+            Answer::Str(val) | Answer::Render(val) => write!(f, "{}", val),
+        }
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(val: u64) -> Self {
+        Answer::Int(val)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(val: u32) -> Self {
+        Answer::Int(val.into())
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(val: i64) -> Self {
+        Answer::Signed(val)
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(val: i32) -> Self {
+        Answer::Signed(val.into())
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(val: usize) -> Self {
+        Answer::Int(val as u64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(val: String) -> Self {
+        Answer::Str(val)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(val: &str) -> Self {
+        Answer::Str(val.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_answer_conversions() {
+        assert_eq!(Answer::from(13140u32), Answer::Int(13140));
+        assert_eq!(Answer::from(24usize), Answer::Int(24));
+        assert_eq!(Answer::from("CMZ"), Answer::Str("CMZ".to_string()));
+        assert_eq!(Answer::from(-3i64).to_string(), "-3");
+
+        assert_eq!(Answer::Int(2713310158).to_string(), "2713310158");
+        assert_eq!(
+            Answer::Render("#.\n.#\n".to_string()).to_string(),
+            "#.\n.#\n"
+        );
+    }
+
+    #[test]
+    fn test_answer_json() -> serde_json::Result<()> {
+        assert_eq!(
+            serde_json::to_string(&Answer::Int(24))?,
+            r#"{"kind":"int","value":24}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&Answer::Signed(-24))?,
+            r#"{"kind":"int","value":-24}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&Answer::from("ab"))?,
+            r#"{"kind":"str","value":"ab"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&Answer::Render("ab\n".to_string()))?,
+            r#"{"kind":"render","value":"ab\n"}"#
+        );
+
+        Ok(())
+    }
+}
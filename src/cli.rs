@@ -0,0 +1,357 @@
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{bail, Error, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use advent_of_code_2022::input::InputSource;
+
+#[derive(Debug, Parser)]
+#[command(name = "aoc", about = "Advent of Code 2022 solutions")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+
+    /// Write every span to a trace file, see --trace-format
+    #[arg(long, global = true, value_name = "PATH")]
+    pub trace: Option<PathBuf>,
+
+    /// Trace file format. `chrome` opens in chrome://tracing or Perfetto, `flame` is folded
+    /// stacks for inferno-flamegraph or flamegraph.pl
+    #[arg(long, global = true, value_enum, default_value_t = TraceFormat::Chrome)]
+    pub trace_format: TraceFormat,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the solvers for one or more days
+    Run(RunArgs),
+    /// Run the solvers and record their answers in answers.toml
+    Record(RunArgs),
+    /// Re-run every input recorded in answers.toml and report mismatches
+    Verify(VerifyArgs),
+    /// Download puzzle inputs that aren't in input/ yet
+    Fetch(FetchArgs),
+    /// Submit a part's answer, computed from input/day_N.txt unless given
+    Submit(SubmitArgs),
+    /// Generate a new day's module and input stubs, and register it
+    NewDay(NewDayArgs),
+    /// Re-run a day whenever its input or example changes, showing what changed
+    Watch(WatchArgs),
+    /// Report each elf's calories from a day 1 input, with summary stats and a histogram
+    ElfStats(ElfStatsArgs),
+    /// Find the best and worst plans against a day 2 guide and rank the guide among random plans
+    Strategy(StrategyArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct RunArgs {
+    /// Days to run, e.g. `12`, `1..=9`, `1..10` or `3,5,7`
+    #[arg(required_unless_present = "all", conflicts_with = "all")]
+    pub days: Option<DaySelection>,
+
+    /// Run every implemented day
+    #[arg(long)]
+    pub all: bool,
+
+    /// Only run the given part
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+
+    /// Input file to use instead of `input/day_N.txt`, or `-` for stdin. Needs a single day
+    #[arg(short, long, value_name = "PATH")]
+    pub input: Option<InputSource>,
+
+    /// Report parse and per part times, then a summary table sorted by cost
+    #[arg(short, long)]
+    pub time: bool,
+
+    /// Output format. `json` prints one JSON object per day and part, timings included
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    /// Run days in parallel on a pool of N threads, 0 for one per CPU. Output stays in day order
+    #[arg(short, long, value_name = "N")]
+    pub jobs: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TraceFormat {
+    Chrome,
+    Flame,
+}
+
+#[derive(Debug, Args)]
+pub struct VerifyArgs {
+    /// Days to verify, every implemented day if not given
+    pub days: Option<DaySelection>,
+}
+
+#[derive(Debug, Args)]
+pub struct FetchArgs {
+    /// Days to fetch. Reads the session token from AOC_SESSION or aoc.toml
+    pub days: DaySelection,
+}
+
+#[derive(Debug, Args)]
+pub struct SubmitArgs {
+    /// Day to submit
+    #[arg(value_parser = clap::value_parser!(u32).range(1..=25))]
+    pub day: u32,
+
+    /// Part to submit
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: u8,
+
+    /// Submit this answer instead of running the solver, e.g. for letters read off a render
+    #[arg(short, long)]
+    pub answer: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct NewDayArgs {
+    /// Day to create
+    #[arg(value_parser = clap::value_parser!(u32).range(1..=25))]
+    pub day: u32,
+}
+
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+    /// Day to watch
+    #[arg(value_parser = clap::value_parser!(u32).range(1..=25))]
+    pub day: u32,
+
+    /// How often to check the inputs for changes
+    #[arg(long, default_value = "500ms")]
+    pub interval: humantime::Duration,
+}
+
+#[derive(Debug, Args)]
+pub struct ElfStatsArgs {
+    /// Input file to use instead of `input/day_1.txt`, or `-` for stdin
+    #[arg(short, long, value_name = "PATH")]
+    pub input: Option<InputSource>,
+
+    /// Number of histogram bins
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u16).range(1..))]
+    pub bins: u16,
+}
+
+#[derive(Debug, Args)]
+pub struct StrategyArgs {
+    /// Input file to use instead of `input/day_2.txt`, or `-` for stdin
+    #[arg(short, long, value_name = "PATH")]
+    pub input: Option<InputSource>,
+
+    /// Game the guide is for
+    #[arg(long, value_enum, default_value_t = GameKind::Rps)]
+    pub game: GameKind,
+
+    /// Also list the best and worst plans round by round
+    #[arg(long)]
+    pub plans: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GameKind {
+    /// Rock, paper, scissors
+    Rps,
+    /// Rock, paper, scissors, lizard, Spock
+    Rpsls,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaySelection(BTreeSet<u32>);
+
+impl DaySelection {
+    pub fn days(&self) -> impl Iterator<Item = u32> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+impl FromStr for DaySelection {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut days = BTreeSet::new();
+
+        for token in s.split(',') {
+            let token = token.trim();
+
+            if let Some((start, end)) = token.split_once("..=") {
+                let (start, end) = (parse_day(start)?, parse_day(end)?);
+                days.extend(start..=end);
+            } else if let Some((start, end)) = token.split_once("..") {
+                let (start, end) = (parse_day(start)?, parse_day(end)?);
+                days.extend(start..end);
+            } else {
+                days.insert(parse_day(token)?);
+            }
+        }
+
+        if days.is_empty() {
+            bail!("Empty day selection: {}", s);
+        }
+
+        Ok(Self(days))
+    }
+}
+
+fn parse_day(token: &str) -> Result<u32> {
+    let day = token
+        .trim()
+        .parse::<u32>()
+        .map_err(|_| Error::msg(format!("Invalid day: {:?}", token)))?;
+
+    if !(1..=25).contains(&day) {
+        bail!("Day out of range: {}", day);
+    }
+
+    Ok(day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn days(selection: &str) -> Result<Vec<u32>> {
+        Ok(selection.parse::<DaySelection>()?.days().collect())
+    }
+
+    #[test]
+    fn test_day_selection() -> Result<()> {
+        assert_eq!(days("12")?, vec![12]);
+        assert_eq!(days("1..=4")?, vec![1, 2, 3, 4]);
+        assert_eq!(days("1..4")?, vec![1, 2, 3]);
+        assert_eq!(days("7,3,5..=6")?, vec![3, 5, 6, 7]);
+
+        assert!(days("0").is_err());
+        assert!(days("26").is_err());
+        assert!(days("4..4").is_err());
+        assert!(days("a..=3").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_args() {
+        let cli = Cli::try_parse_from(["aoc", "run", "11", "--part", "2"]).unwrap();
+        let Command::Run(args) = cli.command else {
+            panic!("Expected run command");
+        };
+        assert_eq!(args.days.unwrap().days().collect::<Vec<_>>(), vec![11]);
+        assert_eq!(args.part, Some(2));
+
+        assert!(Cli::try_parse_from(["aoc", "run", "--all"]).is_ok());
+        assert!(Cli::try_parse_from(["aoc", "run"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "run", "1", "--all"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "run", "1", "--part", "3"]).is_err());
+
+        let cli = Cli::try_parse_from(["aoc", "run", "4", "--input", "-"]).unwrap();
+        let Command::Run(args) = cli.command else {
+            panic!("Expected run command");
+        };
+        assert_eq!(args.input, Some(InputSource::Stdin));
+        assert_eq!(args.format, OutputFormat::Text);
+
+        let cli = Cli::try_parse_from(["aoc", "run", "--all", "--format", "json"]).unwrap();
+        let Command::Run(args) = cli.command else {
+            panic!("Expected run command");
+        };
+        assert_eq!(args.format, OutputFormat::Json);
+        assert_eq!(args.jobs, None);
+
+        let cli = Cli::try_parse_from(["aoc", "run", "1..=9", "-j", "4"]).unwrap();
+        let Command::Run(args) = cli.command else {
+            panic!("Expected run command");
+        };
+        assert_eq!(args.jobs, Some(4));
+    }
+
+    #[test]
+    fn test_record_args() {
+        assert!(Cli::try_parse_from(["aoc", "record", "7", "--part", "2"]).is_ok());
+    }
+
+    #[test]
+    fn test_verify_args() {
+        assert!(Cli::try_parse_from(["aoc", "verify"]).is_ok());
+    }
+
+    #[test]
+    fn test_fetch_args() {
+        assert!(Cli::try_parse_from(["aoc", "fetch", "17"]).is_ok());
+        assert!(Cli::try_parse_from(["aoc", "fetch"]).is_err());
+    }
+
+    #[test]
+    fn test_submit_args() {
+        let cli =
+            Cli::try_parse_from(["aoc", "submit", "10", "2", "--answer", "PLEFULPB"]).unwrap();
+        let Command::Submit(args) = cli.command else {
+            panic!("Expected submit command");
+        };
+        assert_eq!((args.day, args.part), (10, 2));
+        assert_eq!(args.answer.as_deref(), Some("PLEFULPB"));
+        assert!(Cli::try_parse_from(["aoc", "submit", "10", "3"]).is_err());
+    }
+
+    #[test]
+    fn test_new_day_args() {
+        let cli = Cli::try_parse_from(["aoc", "new-day", "17"]).unwrap();
+        let Command::NewDay(args) = cli.command else {
+            panic!("Expected new-day command");
+        };
+        assert_eq!(args.day, 17);
+        assert!(Cli::try_parse_from(["aoc", "new-day", "26"]).is_err());
+    }
+
+    #[test]
+    fn test_watch_args() {
+        let cli = Cli::try_parse_from(["aoc", "watch", "5", "--interval", "2s"]).unwrap();
+        let Command::Watch(args) = cli.command else {
+            panic!("Expected watch command");
+        };
+        assert_eq!(args.day, 5);
+        assert_eq!(*args.interval, std::time::Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_elf_stats_args() {
+        let cli = Cli::try_parse_from(["aoc", "elf-stats", "-i", "-", "--bins", "5"]).unwrap();
+        let Command::ElfStats(args) = cli.command else {
+            panic!("Expected elf-stats command");
+        };
+        assert_eq!((args.input, args.bins), (Some(InputSource::Stdin), 5));
+        assert!(Cli::try_parse_from(["aoc", "elf-stats", "--bins", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_strategy_args() {
+        let cli = Cli::try_parse_from(["aoc", "strategy", "--game", "rpsls", "--plans"]).unwrap();
+        let Command::Strategy(args) = cli.command else {
+            panic!("Expected strategy command");
+        };
+        assert_eq!(
+            (args.game, args.plans, args.input),
+            (GameKind::Rpsls, true, None)
+        );
+        assert!(Cli::try_parse_from(["aoc", "strategy", "--game", "chess"]).is_err());
+    }
+
+    #[test]
+    fn test_trace_args() {
+        let cli = Cli::try_parse_from(["aoc", "run", "14", "--trace", "trace.json"]).unwrap();
+        assert_eq!(cli.trace, Some(PathBuf::from("trace.json")));
+        assert_eq!(cli.trace_format, TraceFormat::Chrome);
+        let cli = Cli::try_parse_from(["aoc", "--trace-format", "flame", "run", "14"]).unwrap();
+        assert_eq!(cli.trace_format, TraceFormat::Flame);
+    }
+}
@@ -0,0 +1,120 @@
+//! Settings for talking to the Advent of Code site, read from `aoc.toml` and the environment.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use toml_edit::{DocumentMut, Item};
+
+/// Local, untracked settings file. Holds the session token, so keep it out of git.
+pub const CONFIG_PATH: &str = "aoc.toml";
+/// Environment variable holding the session token. Takes priority over the config file.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+/// Puzzle year every day belongs to.
+pub const YEAR: u32 = 2022;
+
+/// Site settings.
+///
+/// ```toml
+/// session = "53616c7465645f5f..."
+/// base_url = "https://adventofcode.com"
+/// cache_dir = ".aoc-cache"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Value of the site's `session` cookie.
+    pub session: Option<String>,
+    /// Site to talk to, without a trailing slash.
+    pub base_url: String,
+    /// Where successful responses are cached.
+    pub cache_dir: PathBuf,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            session: None,
+            base_url: "https://adventofcode.com".to_string(),
+            cache_dir: PathBuf::from(".aoc-cache"),
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config file, treating a missing file as empty, then applies the environment.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+
+        let mut config = if path.exists() {
+            std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?
+                .parse::<Config>()
+                .with_context(|| format!("Invalid config file {}", path.display()))?
+        } else {
+            Config::default()
+        };
+
+        if let Some(session) = std::env::var(SESSION_VAR).ok().filter(|s| !s.is_empty()) {
+            config.session = Some(session);
+        }
+
+        Ok(config)
+    }
+
+    /// URL of a day's puzzle input.
+    pub fn input_url(&self, day: u32) -> String {
+        format!("{}/{}/day/{}/input", self.base_url, YEAR, day)
+    }
+
+    /// URL answers for a day are posted to.
+    pub fn answer_url(&self, day: u32) -> String {
+        format!("{}/{}/day/{}/answer", self.base_url, YEAR, day)
+    }
+}
+
+impl std::str::FromStr for Config {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let doc = s.parse::<DocumentMut>()?;
+        let get = |key: &str| doc.get(key).and_then(Item::as_str).map(str::to_string);
+
+        let mut config = Config {
+            session: get("session"),
+            ..Config::default()
+        };
+        if let Some(base_url) = get("base_url") {
+            config.base_url = base_url.trim_end_matches('/').to_string();
+        }
+        if let Some(cache_dir) = get("cache_dir") {
+            config.cache_dir = PathBuf::from(cache_dir);
+        }
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() -> Result<()> {
+        let config =
+            "session = \"abc\"\nbase_url = \"http://127.0.0.1:8080/\"\n".parse::<Config>()?;
+
+        assert_eq!(config.session.as_deref(), Some("abc"));
+        assert_eq!(
+            config.input_url(5),
+            "http://127.0.0.1:8080/2022/day/5/input"
+        );
+        assert_eq!(
+            config.answer_url(5),
+            "http://127.0.0.1:8080/2022/day/5/answer"
+        );
+        assert_eq!(config.cache_dir, PathBuf::from(".aoc-cache"));
+
+        assert_eq!("".parse::<Config>()?, Config::default());
+
+        Ok(())
+    }
+}
@@ -1,64 +1,422 @@
-use std::collections::BTreeSet;
-use std::fs::File;
+//! Day 1: Calorie Counting.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::Write;
 use std::io::BufRead;
 
-use anyhow::Result;
+use anyhow::{bail, Error, Result};
+
+use crate::answer::Answer;
+use crate::parse::{self, Line, ParseResult};
+use crate::solution::Solution;
 
-#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
-struct Elf {
+/// An elf, where it was listed and the food it carries.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Elf {
+    index: usize,
+    line: usize,
+    items: Vec<u64>,
     calories: u64,
 }
 
 impl Elf {
-    fn push_food(&mut self, food: u64) {
-        self.calories += food;
+    /// Position among the elves in the input, from 0.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// 1-based line the elf's first item is on.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Calories of each item, in input order.
+    pub fn items(&self) -> &[u64] {
+        &self.items
+    }
+
+    /// Total calories carried.
+    pub fn calories(&self) -> u64 {
+        self.calories
+    }
+
+    fn push_food(&mut self, food: u64) -> Option<()> {
+        self.calories = self.calories.checked_add(food)?;
+        self.items.push(food);
+        Some(())
+    }
+}
+
+/// Groups numbered lines into elves, one line at a time, so the input doesn't have to be held
+/// in memory to be read.
+#[derive(Debug, Default)]
+struct ElfBuilder {
+    count: usize,
+    current: Option<Elf>,
+}
+
+impl ElfBuilder {
+    /// Adds a line, returning the elf a blank line finishes.
+    fn push(&mut self, line: Line<'_>) -> ParseResult<Option<Elf>> {
+        if line.text.is_empty() {
+            return Ok(self.current.take());
+        }
+
+        let elf = self.current.get_or_insert_with(|| {
+            self.count += 1;
+            Elf {
+                index: self.count - 1,
+                line: line.number,
+                ..Elf::default()
+            }
+        });
+        let calories = line.parse::<u64>(line.text, "calorie count")?;
+        elf.push_food(calories)
+            .ok_or_else(|| line.error(line.text, "Calorie total too large"))?;
+
+        Ok(None)
+    }
+
+    /// The elf left over at the end of the input.
+    fn finish(&mut self) -> Option<Elf> {
+        self.current.take()
     }
 }
 
-fn build_elf_set() -> Result<BTreeSet<Elf>> {
-    let file = File::open("input/day_1.txt")?;
+/// Reads elves one at a time from blank line separated groups of calorie counts, in input
+/// order. Any number of blank lines may separate two elves, and the last group doesn't need
+/// one after it.
+pub fn elves(input: &str) -> impl Iterator<Item = ParseResult<Elf>> + '_ {
+    let mut lines = parse::lines(input);
+    let mut builder = ElfBuilder::default();
 
-    let mut elves = BTreeSet::new();
-    let mut elf = Elf::default();
-    let lines = std::io::BufReader::new(file).lines();
-    for line in lines.flatten() {
-        if line.is_empty() {
-            let old_elf = std::mem::take(&mut elf);
-            elves.insert(old_elf);
-        } else {
-            let calories = line.parse::<u64>()?;
-            elf.push_food(calories);
+    std::iter::from_fn(move || {
+        for line in lines.by_ref() {
+            if let Some(elf) = builder.push(line).transpose() {
+                return Some(elf);
+            }
         }
+
+        builder.finish().map(Ok)
+    })
+}
+
+/// Like [`elves`], but streams the input from `reader` a line at a time instead of needing it
+/// all in memory. Fails on the first line that can't be read, as well as on bad input.
+pub fn read_elves(reader: impl BufRead) -> impl Iterator<Item = Result<Elf>> {
+    let mut lines = reader.lines().enumerate();
+    let mut builder = ElfBuilder::default();
+
+    std::iter::from_fn(move || {
+        for (idx, text) in lines.by_ref() {
+            let text = match text {
+                Ok(text) => text,
+                Err(e) => {
+                    let error = Error::new(e).context(format!("Failed to read line {}", idx + 1));
+                    return Some(Err(error));
+                }
+            };
+            let line = Line {
+                number: idx + 1,
+                text: &text,
+            };
+            if let Some(elf) = builder.push(line).transpose() {
+                return Some(elf.map_err(Error::new));
+            }
+        }
+
+        builder.finish().map(Ok)
+    })
+}
+
+/// Parses every elf, in input order.
+pub fn build_elf_list(input: &str) -> ParseResult<Vec<Elf>> {
+    elves(input).collect()
+}
+
+/// Sum of the `n` largest calorie totals, keeping only `n` totals around while going through
+/// `elves`. Elves carrying the same total each count.
+pub fn top_calories<'a>(elves: impl IntoIterator<Item = &'a Elf>, n: usize) -> Result<u64> {
+    let mut top = BinaryHeap::with_capacity(n + 1);
+    let mut elf_count = 0;
+    for elf in elves {
+        elf_count += 1;
+        top.push(Reverse(elf.calories));
+        if top.len() > n {
+            top.pop();
+        }
+    }
+
+    if elf_count < n {
+        bail!("Can't sum the top {} elves, only found {}", n, elf_count);
     }
 
-    Ok(elves)
+    top.into_iter()
+        .try_fold(0u64, |sum, Reverse(calories)| sum.checked_add(calories))
+        .ok_or_else(|| Error::msg(format!("Top {} calorie totals overflow", n)))
 }
 
-pub fn day_1() -> Result<()> {
-    day_1_1()?;
-    day_1_2()
+/// Mean, median and standard deviation of the elves' calorie totals.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CalorieStats {
+    /// Average total.
+    pub mean: f64,
+    /// Middle total, or the average of the two middle ones for an even number of elves.
+    pub median: f64,
+    /// Population standard deviation of the totals.
+    pub std_dev: f64,
 }
 
-pub fn day_1_1() -> Result<()> {
-    let elves = build_elf_set()?;
+impl CalorieStats {
+    /// Stats over every elf, or `None` without any.
+    pub fn new(elves: &[Elf]) -> Option<Self> {
+        let mut totals = elves.iter().map(Elf::calories).collect::<Vec<_>>();
+        totals.sort_unstable();
 
-    let calories = elves.into_iter().last().unwrap().calories;
-    println!("Day 1-1: {}", calories);
+        let count = totals.len() as f64;
+        let mean = totals.iter().map(|total| *total as f64).sum::<f64>() / count;
+        let median = match totals.len() {
+            0 => return None,
+            len if !len.is_multiple_of(2) => totals[len / 2] as f64,
+            len => (totals[len / 2 - 1] as f64 + totals[len / 2] as f64) / 2.0,
+        };
+        let variance = totals
+            .iter()
+            .map(|total| (*total as f64 - mean).powi(2))
+            .sum::<f64>()
+            / count;
+
+        Some(Self {
+            mean,
+            median,
+            std_dev: variance.sqrt(),
+        })
+    }
+}
 
-    Ok(())
+/// Elves whose total falls in `start..=end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bin {
+    /// Smallest total in the bin.
+    pub start: u64,
+    /// Largest total in the bin.
+    pub end: u64,
+    /// Number of elves in the bin.
+    pub count: usize,
 }
 
-pub fn day_1_2() -> Result<()> {
-    let elves = build_elf_set()?;
+/// Splits the range of totals into at most `bins` equally wide bins and counts the elves in
+/// each. Bins past the largest total are left out.
+pub fn histogram(elves: &[Elf], bins: usize) -> Vec<Bin> {
+    let totals = elves.iter().map(Elf::calories);
+    let (Some(min), Some(max)) = (totals.clone().min(), totals.max()) else {
+        return Vec::new();
+    };
 
-    let mut elf_iter = elves.into_iter().rev();
-    let mut top_3_cals = 0;
-    for _ in 0..3 {
-        let elf = elf_iter.next().unwrap();
-        top_3_cals += elf.calories;
+    // In u128, as the width of a single bin spanning every u64 doesn't fit in a u64
+    let range = u128::from(max - min);
+    let width = range / bins.max(1) as u128 + 1;
+    let mut histogram = (0..=range / width)
+        .map(|idx| {
+            // Bins start at or before `max`, so only the end can go past u64::MAX
+            let start = u128::from(min) + idx * width;
+            Bin {
+                start: start as u64,
+                end: (start + width - 1).min(u128::from(u64::MAX)) as u64,
+                count: 0,
+            }
+        })
+        .collect::<Vec<_>>();
+    for elf in elves {
+        histogram[(u128::from(elf.calories - min) / width) as usize].count += 1;
     }
 
-    println!("Day 1-2: {}", top_3_cals);
+    histogram
+}
+
+/// Text report with one row per elf, then the stats and a histogram of the totals over
+/// `bins` bins.
+pub fn report(elves: &[Elf], bins: usize) -> String {
+    const BAR_WIDTH: usize = 40;
+
+    let mut report = String::new();
+    let _ = writeln!(
+        report,
+        "{:>5} {:>6} {:>5} {:>9}",
+        "elf", "line", "items", "calories"
+    );
+    for elf in elves {
+        let _ = writeln!(
+            report,
+            "{:>5} {:>6} {:>5} {:>9}",
+            elf.index,
+            elf.line,
+            elf.items.len(),
+            elf.calories
+        );
+    }
 
-    Ok(())
+    let Some(stats) = CalorieStats::new(elves) else {
+        report.push_str("\nNo elves\n");
+        return report;
+    };
+    let _ = writeln!(report, "\nelves:   {}", elves.len());
+    let _ = writeln!(report, "mean:    {:.1}", stats.mean);
+    let _ = writeln!(report, "median:  {:.1}", stats.median);
+    let _ = writeln!(report, "std dev: {:.1}\n", stats.std_dev);
+
+    let histogram = histogram(elves, bins);
+    let most = histogram.iter().map(|bin| bin.count).max().unwrap_or(0);
+    let digits = histogram.last().map_or(1, |bin| bin.end.to_string().len());
+    for bin in &histogram {
+        // Any elf at all gets at least one mark, so sparse bins don't look empty
+        let bar = (bin.count * BAR_WIDTH).div_ceil(most.max(1));
+        let _ = writeln!(
+            report,
+            "{:>digits$}-{:<digits$} | {} {}",
+            bin.start,
+            bin.end,
+            "#".repeat(bar),
+            bin.count
+        );
+    }
+
+    report
+}
+
+/// [`Solution`] for day 1.
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u32 = 1;
+
+    type Input = Vec<Elf>;
+
+    fn parse(input: &str) -> ParseResult<Self::Input> {
+        build_elf_list(input)
+    }
+
+    fn part1(elves: &Self::Input) -> Result<Answer> {
+        day_1_1(elves)
+    }
+
+    fn part2(elves: &Self::Input) -> Result<Answer> {
+        day_1_2(elves)
+    }
+}
+
+/// Calories carried by the elf carrying the most.
+pub fn day_1_1(elves: &[Elf]) -> Result<Answer> {
+    Ok(top_calories(elves, 1)?.into())
+}
+
+/// Calories carried by the top three elves.
+pub fn day_1_2(elves: &[Elf]) -> Result<Answer> {
+    Ok(top_calories(elves, 3)?.into())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parse::ParseError;
+
+    #[test]
+    fn test_top_calories() -> Result<()> {
+        // Two elves tie for second, and the last one has no blank line after it
+        let elves = build_elf_list("1000\n2000\n\n\n\n4000\n\n3000\n1000\n\n500\n\n0\n")?;
+
+        let totals = elves.iter().map(Elf::calories).collect::<Vec<_>>();
+        assert_eq!(totals, vec![3000, 4000, 4000, 500, 0]);
+        assert_eq!(top_calories(&elves, 1)?, 4000);
+        assert_eq!(top_calories(&elves, 3)?, 11000);
+        assert_eq!(top_calories(&elves, 5)?, 11500);
+        assert_eq!(top_calories(&elves, 0)?, 0);
+        assert!(top_calories(&elves, 6).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stats() -> Result<()> {
+        let elves = build_elf_list("1000\n2000\n\n\n\n4000\n\n3000\n1000\n\n500\n\n0\n")?;
+
+        assert_eq!((elves[2].index(), elves[2].line()), (2, 8));
+        assert_eq!(elves[2].items(), &[3000, 1000]);
+
+        let stats = CalorieStats::new(&elves).unwrap();
+        assert_eq!((stats.mean, stats.median), (2300.0, 3000.0));
+        assert!((stats.std_dev - 2960000f64.sqrt()).abs() < 1e-9);
+        assert_eq!(CalorieStats::new(&[]), None);
+
+        let counts = histogram(&elves, 4)
+            .iter()
+            .map(|bin| (bin.start, bin.end, bin.count))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            counts,
+            vec![
+                (0, 1000, 2),
+                (1001, 2001, 0),
+                (2002, 3002, 1),
+                (3003, 4003, 2)
+            ]
+        );
+        assert_eq!(
+            histogram(&elves[1..2], 4),
+            vec![Bin {
+                start: 4000,
+                end: 4000,
+                count: 1
+            }]
+        );
+
+        // A range spanning every u64 still gets a bin
+        let extremes = build_elf_list("0\n\n18446744073709551615\n")?;
+        assert_eq!(
+            histogram(&extremes, 1),
+            vec![Bin {
+                start: 0,
+                end: u64::MAX,
+                count: 2
+            }]
+        );
+        assert_eq!(histogram(&extremes, 2).len(), 2);
+
+        let report = report(&elves, 4);
+        assert!(report.contains("\n    2      8     2      4000\n"));
+        assert!(report.contains("\nstd dev: 1720.5\n"));
+        assert!(report.contains("\n   0-1000 | ######################################## 2\n"));
+        assert!(report.contains("\n2002-3002 | #################### 1\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_elves() -> Result<()> {
+        let input = "1000\n2000\n\n\n\n4000\n\n3000\n1000\n\n500\n\n0\n";
+
+        let streamed = read_elves(input.as_bytes()).collect::<Result<Vec<_>>>()?;
+        assert_eq!(streamed, build_elf_list(input)?);
+
+        let error = read_elves("1000\n\n2000\nabc\n".as_bytes())
+            .collect::<Result<Vec<_>>>()
+            .unwrap_err();
+        let error = error.downcast::<ParseError>()?;
+        assert_eq!((error.line, error.column), (4, 1));
+
+        let error = read_elves(&b"1000\n\xff\n"[..])
+            .collect::<Result<Vec<_>>>()
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Failed to read line 2");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = build_elf_list("1000\n\n2000\nabc\n").unwrap_err();
+        assert_eq!((error.line, error.column), (4, 1));
+        assert!(build_elf_list("").unwrap().is_empty());
+    }
 }
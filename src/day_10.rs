@@ -1,8 +1,15 @@
+//! Day 10: Cathode-Ray Tube.
+
 use std::collections::{HashMap, VecDeque};
 
-use anyhow::{bail, Error, Result};
+use anyhow::Result;
 use tracing::debug;
 
+use crate::answer::Answer;
+use crate::grid::Grid;
+use crate::parse::{self, Line, ParseError, ParseResult};
+use crate::solution::Solution;
+
 #[derive(Debug, Clone, Copy)]
 struct CycleCount(u32);
 
@@ -21,16 +28,14 @@ enum Op {
     Addx(i32),
 }
 
-impl TryFrom<&str> for Op {
-    type Error = Error;
-
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let tokens = value.split(' ').collect::<Vec<_>>();
+impl TryFrom<Line<'_>> for Op {
+    type Error = ParseError;
 
-        let op = match tokens[0] {
-            "noop" => Op::Noop,
-            "addx" => Op::Addx(tokens[1].parse::<i32>()?),
-            _ => bail!("Invalid op: {}", tokens[0]),
+    fn try_from(line: Line<'_>) -> Result<Self, Self::Error> {
+        let op = match line.text.split_once(' ') {
+            None if line.text == "noop" => Op::Noop,
+            Some(("addx", value)) => Op::Addx(line.parse::<i32>(value, "addx value")?),
+            _ => return Err(line.error(line.text, "Invalid op")),
         };
 
         Ok(op)
@@ -65,15 +70,18 @@ impl Sprite {
     }
 }
 
-#[derive(Debug)]
-struct Cpu {
+/// The CPU's pending instructions, `X` register and CRT display.
+#[derive(Debug, Clone)]
+pub struct Cpu {
     ops: VecDeque<Op>,
     x: i32,
-    display: Vec<Vec<char>>,
+    display: Grid<char>,
 }
 
 impl Cpu {
-    fn parse_signal(&mut self) -> Result<i32> {
+    /// Runs the program for 240 cycles, drawing the display as it goes, and returns the
+    /// sum of the signal strengths at cycles 20, 60, ..., 220.
+    pub fn parse_signal(&mut self) -> Result<i32> {
         let mut strength_map = (20u32..221u32)
             .step_by(40)
             .map(|cycle| (cycle, 0i32))
@@ -94,7 +102,7 @@ impl Cpu {
             if sprite.contains(col as i32) {
                 let row = pixel / 40;
 
-                self.display[row][col] = '#';
+                self.display[(col, row)] = '#';
             }
 
             if let Some(strength) = strength_map.get_mut(&cycle) {
@@ -126,50 +134,63 @@ impl Cpu {
         Ok(strength_map.values().sum())
     }
 
-    fn render_display(&self) -> String {
-        let mut render = String::new();
+    /// The display as `#` and `.` rows, one line each.
+    pub fn render_display(&self) -> String {
+        self.display.render(|pixel| *pixel)
+    }
+}
 
-        for line in &self.display {
-            let mut line = String::from_iter(line);
-            line.push('\n');
-            render.push_str(&line);
-        }
+/// [`Solution`] for day 10.
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u32 = 10;
+
+    type Input = Cpu;
+
+    fn parse(input: &str) -> ParseResult<Self::Input> {
+        parse(input)
+    }
+
+    fn part1(cpu: &Self::Input) -> Result<Answer> {
+        day_10_1(cpu)
+    }
 
-        render
+    fn part2(cpu: &Self::Input) -> Result<Answer> {
+        day_10_2(cpu)
     }
 }
 
-pub fn day_10() -> Result<()> {
-    let mut cpu = parse()?;
+/// Sum of the six sampled signal strengths.
+pub fn day_10_1(cpu: &Cpu) -> Result<Answer> {
+    let mut cpu = cpu.clone();
 
     let signal_strength = cpu.parse_signal()?;
-    println!("Day 10-1: {}", signal_strength);
-
-    let display = cpu.render_display();
-    print!("Day 10-2:\n{}", display);
 
-    Ok(())
+    Ok(signal_strength.into())
 }
 
-fn parse() -> Result<Cpu> {
-    let input = std::fs::read_to_string("input/day_10.txt")?;
+/// The letters drawn on the CRT.
+pub fn day_10_2(cpu: &Cpu) -> Result<Answer> {
+    let mut cpu = cpu.clone();
 
-    parse_inner(input)
+    cpu.parse_signal()?;
+    let display = cpu.render_display();
+
+    Ok(Answer::Render(display))
 }
 
-fn parse_inner(input: String) -> Result<Cpu> {
-    let ops = input
-        .lines()
+/// Parses one `noop` or `addx V` instruction per line.
+pub fn parse(input: &str) -> ParseResult<Cpu> {
+    let ops = parse::lines(input)
         .map(Op::try_from)
-        .collect::<Result<VecDeque<_>>>()?;
-
-    let mut display = Vec::with_capacity(6);
-    let display_row = (0..40).map(|_| '.').collect::<Vec<_>>();
-    for _ in 0..6 {
-        display.push(display_row.clone());
-    }
+        .collect::<ParseResult<VecDeque<_>>>()?;
 
-    Ok(Cpu { ops, x: 1, display })
+    Ok(Cpu {
+        ops,
+        x: 1,
+        display: Grid::new(40, 6, '.'),
+    })
 }
 
 #[cfg(test)]
@@ -189,7 +210,7 @@ mod test {
 
         let input = std::fs::read_to_string("input/day_10_test.txt")?;
 
-        let mut cpu = parse_inner(input)?;
+        let mut cpu = parse(&input)?;
 
         let signal_strength = cpu.parse_signal()?;
         assert_eq!(signal_strength, 13140);
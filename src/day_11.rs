@@ -1,24 +1,31 @@
-use anyhow::{bail, Result};
+//! Day 11: Monkey in the Middle.
+
+use anyhow::{bail, Error, Result};
 use itertools::Itertools;
-use tracing::debug;
+use tracing::{debug, instrument};
+
+use crate::answer::Answer;
+use crate::parse::{self, ParseError, ParseResult};
+use crate::solution::Solution;
 
-type MonkeFn = fn(u64, u64) -> u64;
+type MonkeFn = fn(u64, u64) -> Option<u64>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum MonkeOp {
     MonkeSelf(MonkeFn),
     MonkeOther((MonkeFn, u64)),
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 struct Test {
     div: u64,
     t_case: usize,
     f_case: usize,
 }
 
-#[derive(Debug)]
-struct Monke {
+/// A monkey's held items, its worry operation and throw test, and how many items it has inspected.
+#[derive(Debug, Clone)]
+pub struct Monke {
     items: Vec<u64>,
     monke_op: MonkeOp,
     test: Test,
@@ -30,10 +37,14 @@ impl Monke {
         let mut results = Vec::new();
 
         for mut item in self.items.drain(..) {
-            match self.monke_op {
-                MonkeOp::MonkeSelf(op) => item = op(item, item),
-                MonkeOp::MonkeOther((op, other)) => item = op(item, other),
-            }
+            let new_item = match self.monke_op {
+                MonkeOp::MonkeSelf(op) => op(item, item),
+                MonkeOp::MonkeOther((op, other)) => op(item, other),
+            };
+            let Some(new_item) = new_item else {
+                bail!("Worry level overflowed inspecting {}", item);
+            };
+            item = new_item;
 
             if worry {
                 item /= 3;
@@ -51,7 +62,10 @@ impl Monke {
     }
 }
 
-fn run_rounds(monkes: &mut Vec<Monke>, rounds: u32, worry: bool, divider: u64) -> Result<()> {
+/// Plays `rounds` rounds of keep away. With `worry` set, worry levels are divided by 3
+/// after each inspection; otherwise they are kept modulo `divider`.
+#[instrument(skip(monkes))]
+pub fn run_rounds(monkes: &mut Vec<Monke>, rounds: u32, worry: bool, divider: u64) -> Result<()> {
     for _ in 0..rounds {
         for idx in 0..monkes.len() {
             let moves = monkes[idx].run_round(worry, divider)?;
@@ -66,14 +80,31 @@ fn run_rounds(monkes: &mut Vec<Monke>, rounds: u32, worry: bool, divider: u64) -
     Ok(())
 }
 
-fn top_touches(monkes: &[Monke]) -> u64 {
+/// Product of every monkey's divisor, which worry levels can be kept modulo without
+/// changing any throw.
+pub fn common_divider(monkes: &[Monke]) -> Result<u64> {
+    monkes.iter().try_fold(1u64, |divider, monke| {
+        divider
+            .checked_mul(monke.test.div)
+            .ok_or_else(|| Error::msg("Monkey divisors multiply past u64"))
+    })
+}
+
+/// Monkey business: the product of the two highest inspection counts.
+pub fn top_touches(monkes: &[Monke]) -> Result<u64> {
     let sorted_touches = monkes
         .iter()
         .map(|monke| monke.touches)
         .sorted()
         .rev()
         .collect::<Vec<_>>();
-    sorted_touches[..2].iter().product::<u64>()
+    let [first, second, ..] = sorted_touches[..] else {
+        bail!("Monkey business needs two monkeys, found {}", monkes.len());
+    };
+
+    first
+        .checked_mul(second)
+        .ok_or_else(|| Error::msg("Monkey business too large"))
 }
 
 impl Default for Monke {
@@ -81,104 +112,148 @@ impl Default for Monke {
         Self {
             items: Default::default(),
             test: Default::default(),
-            monke_op: MonkeOp::MonkeSelf(|var1, _var2| var1),
+            monke_op: MonkeOp::MonkeSelf(|var1, _var2| Some(var1)),
             touches: u64::default(),
         }
     }
 }
 
-pub fn day_11() -> Result<()> {
-    day_11_1()?;
-    day_11_2()
+/// [`Solution`] for day 11.
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u32 = 11;
+
+    type Input = Vec<Monke>;
+
+    fn parse(input: &str) -> ParseResult<Self::Input> {
+        parse(input)
+    }
+
+    fn part1(monkes: &Self::Input) -> Result<Answer> {
+        day_11_1(monkes)
+    }
+
+    fn part2(monkes: &Self::Input) -> Result<Answer> {
+        day_11_2(monkes)
+    }
 }
 
-fn day_11_1() -> Result<()> {
-    let mut monkes = parse("input/day_11.txt")?;
+/// Monkey business after 20 rounds.
+pub fn day_11_1(monkes: &[Monke]) -> Result<Answer> {
+    let mut monkes = monkes.to_vec();
 
     run_rounds(&mut monkes, 20, true, u64::MAX)?;
 
-    println!("Day 11-1: {}", top_touches(&monkes));
-
-    Ok(())
+    Ok(top_touches(&monkes)?.into())
 }
 
-fn day_11_2() -> Result<()> {
-    let mut monkes = parse("input/day_11.txt")?;
+/// Monkey business after 10000 rounds without worry relief.
+pub fn day_11_2(monkes: &[Monke]) -> Result<Answer> {
+    let mut monkes = monkes.to_vec();
 
-    let divider = monkes.iter().map(|monke| monke.test.div).product();
+    let divider = common_divider(&monkes)?;
     run_rounds(&mut monkes, 10000, false, divider)?;
 
-    println!("Day 11-1: {}", top_touches(&monkes));
-
-    Ok(())
+    Ok(top_touches(&monkes)?.into())
 }
 
-fn parse(path: &str) -> Result<Vec<Monke>> {
-    let input = std::fs::read_to_string(path)?;
-
+/// Parses the monkey descriptions.
+pub fn parse(input: &str) -> ParseResult<Vec<Monke>> {
     let mut monkes = Vec::new();
+    let mut throws = Vec::new();
 
     let mut cur_monke = None;
-    for line in input.lines() {
-        let line = line.trim();
+    let mut line_count = 0;
+    for line in parse::lines(input) {
+        line_count = line.number;
+        let text = line.text.trim();
 
-        if line.is_empty() {
+        if text.is_empty() {
             continue;
         }
 
-        let tokens = line.split(':').collect::<Vec<_>>();
-        if tokens[0].contains("Monkey") {
+        let (key, value) = text
+            .split_once(':')
+            .ok_or_else(|| line.error(text, "Expected a \"key: value\" line"))?;
+        if key.starts_with("Monkey") {
             cur_monke = Some(Monke::default());
             continue;
         }
 
-        if let Some(monke) = &mut cur_monke {
-            match tokens[0] {
-                "Starting items" => {
-                    let items = tokens[1].split(',').collect::<Vec<_>>();
-
-                    for item in items {
-                        let item_int = item.trim().parse::<u64>()?;
-                        monke.items.push(item_int);
-                    }
-                }
-                "Operation" => {
-                    let tokens = tokens[1].trim().split(' ').collect::<Vec<_>>();
-
-                    let monke_fn = match tokens[3] {
-                        "*" => |var1, var2| var1 * var2,
-                        "+" => |var1, var2| var1 + var2,
-                        _ => bail!("Invalid op: {}", tokens[2]),
-                    };
-
-                    if tokens[4] == "old" {
-                        monke.monke_op = MonkeOp::MonkeSelf(monke_fn);
-                    } else {
-                        let monke_int = tokens[4].parse::<u64>()?;
-                        monke.monke_op = MonkeOp::MonkeOther((monke_fn, monke_int));
-                    }
+        let monke = cur_monke
+            .as_mut()
+            .ok_or_else(|| line.error(text, "Expected a \"Monkey N:\" line"))?;
+        let value = value.trim();
+        match key {
+            "Starting items" => {
+                let items = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty());
+
+                for item in items {
+                    let item_int = line.parse::<u64>(item, "item")?;
+                    monke.items.push(item_int);
                 }
-                "Test" => {
-                    let tokens = tokens[1].trim().split(' ').collect::<Vec<_>>();
-                    monke.test.div = tokens[2].parse()?;
+            }
+            "Operation" => {
+                let [_, _, _, op, operand] = line.split(value, " ")?;
+
+                let monke_fn: MonkeFn = match op {
+                    "*" => u64::checked_mul,
+                    "+" => u64::checked_add,
+                    _ => return Err(line.error(op, "Invalid op")),
+                };
+
+                if operand == "old" {
+                    monke.monke_op = MonkeOp::MonkeSelf(monke_fn);
+                } else {
+                    let monke_int = line.parse::<u64>(operand, "operand")?;
+                    monke.monke_op = MonkeOp::MonkeOther((monke_fn, monke_int));
                 }
-                "If true" => {
-                    monke.test.t_case =
-                        tokens[1].trim().split(' ').collect::<Vec<_>>()[3].parse()?;
+            }
+            "Test" => {
+                let [_, _, div] = line.split(value, " ")?;
+                monke.test.div = line.parse(div, "divisor")?;
+                if monke.test.div == 0 {
+                    return Err(line.error(div, "Divisor can't be 0"));
                 }
-                "If false" => {
-                    monke.test.f_case =
-                        tokens[1].trim().split(' ').collect::<Vec<_>>()[3].parse()?;
+            }
+            "If true" | "If false" => {
+                let [_, _, _, target] = line.split(value, " ")?;
+                let target_idx = line.parse::<usize>(target, "monkey")?;
+                throws.push((line, target, target_idx));
+
+                if key == "If true" {
+                    monke.test.t_case = target_idx;
+                } else {
+                    monke.test.f_case = target_idx;
+                    if monke.test.div == 0 {
+                        return Err(line.error(text, "Monkey has no Test line"));
+                    }
 
                     if let Some(monke) = cur_monke.take() {
                         monkes.push(monke);
                     }
                 }
-                _ => bail!("Invalid line: {}", tokens[0]),
             }
+            _ => return Err(line.error(key, "Invalid line")),
         }
     }
 
+    if cur_monke.is_some() {
+        return Err(ParseError::end_of_input(
+            line_count,
+            "Monkey description ends early",
+        ));
+    }
+
+    // Throws can point forward, so targets are only checked once every monkey is known
+    if let Some((line, target, _)) = throws.iter().find(|(_, _, idx)| *idx >= monkes.len()) {
+        return Err(line.error(target, "No such monkey"));
+    }
+
     Ok(monkes)
 }
 
@@ -188,10 +263,11 @@ mod test {
 
     #[test]
     fn test_monke_worry() -> Result<()> {
-        let mut monkes = parse("input/day_11_test.txt")?;
+        let input = std::fs::read_to_string("input/day_11_test.txt")?;
+        let mut monkes = parse(&input)?;
 
         run_rounds(&mut monkes, 20, true, u64::MAX)?;
-        let touches = top_touches(&monkes);
+        let touches = top_touches(&monkes)?;
 
         assert_eq!(touches, 10605);
 
@@ -200,11 +276,12 @@ mod test {
 
     #[test]
     fn test_monke_no_worry() -> Result<()> {
-        let mut monkes = parse("input/day_11_test.txt")?;
+        let input = std::fs::read_to_string("input/day_11_test.txt")?;
+        let mut monkes = parse(&input)?;
 
-        let divider = monkes.iter().map(|monke| monke.test.div).product();
+        let divider = common_divider(&monkes)?;
         run_rounds(&mut monkes, 10000, false, divider)?;
-        let touches = top_touches(&monkes);
+        let touches = top_touches(&monkes)?;
 
         assert_eq!(touches, 2713310158);
 
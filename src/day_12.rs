@@ -1,71 +1,70 @@
-use std::collections::{HashSet, VecDeque};
+//! Day 12: Hill Climbing Algorithm.
+
+use std::collections::VecDeque;
 
 use anyhow::{bail, Result};
+use tracing::instrument;
+
+use crate::answer::Answer;
+use crate::grid::Grid;
+use crate::parse::{self, ParseError, ParseResult};
+use crate::solution::Solution;
 
-struct TopoMap {
-    grid: Vec<Vec<char>>,
+/// Heightmap of the area, `a`-`z` with the start `S` and goal `E` marked.
+pub struct TopoMap {
+    grid: Grid<char>,
 }
 
 impl TopoMap {
-    const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
-
-    fn find_shortest_path(&self, include_a: bool) -> Result<u32> {
+    /// Fewest steps to `E` from `S`, or from any square at height `a` when `include_a` is set.
+    #[instrument(skip(self))]
+    pub fn find_shortest_path(&self, include_a: bool) -> Result<u32> {
         let starting_locs = self.find_start(include_a)?;
 
-        let mut shortest_path = u32::MAX;
-        for (row, col) in starting_locs {
-            if let Some(new_shortest) = self.find_shortest_path_inner(row, col)? {
-                shortest_path = std::cmp::min(shortest_path, new_shortest);
+        let mut shortest_path: Option<u32> = None;
+        for start in starting_locs {
+            if let Some(new_shortest) = self.find_shortest_path_inner(start)? {
+                shortest_path = Some(shortest_path.unwrap_or(u32::MAX).min(new_shortest));
             }
         }
 
-        Ok(shortest_path)
+        match shortest_path {
+            Some(shortest_path) => Ok(shortest_path),
+            None => bail!("E can't be reached"),
+        }
+    }
+
+    /// Height of a square, `S` being at height `a` and `E` at `z`.
+    fn height(square: char) -> u32 {
+        match square {
+            'S' => 'a' as u32,
+            'E' => 'z' as u32,
+            square => square as u32,
+        }
     }
 
-    fn find_shortest_path_inner(&self, start_row: usize, start_col: usize) -> Result<Option<u32>> {
+    #[instrument(level = "debug", skip(self))]
+    fn find_shortest_path_inner(&self, start: (usize, usize)) -> Result<Option<u32>> {
         let mut q = VecDeque::new();
-        let mut visited = HashSet::new();
-
-        let row_len = self.grid.len() as isize;
-        let col_len = self.grid[0].len() as isize;
-
-        q.push_back((start_row, start_col, 0));
-        visited.insert((start_row, start_col));
-
-        // The typecasts in here are brain dead. Can't be bothered to fix
-        while let Some((row, col, path_len)) = q.pop_front() {
-            let cur_char = if self.grid[row][col] == 'S' {
-                'a'
-            } else {
-                self.grid[row][col]
-            };
-
-            for (x, y) in Self::DIRECTIONS {
-                let new_row = row as isize + x;
-                let new_col = col as isize + y;
-
-                if new_row >= 0 && new_row < row_len && new_col >= 0 && new_col < col_len {
-                    if visited.contains(&(new_row as usize, new_col as usize)) {
-                        continue;
-                    }
-
-                    let next_char = self.grid[new_row as usize][new_col as usize];
-                    if next_char == 'E' {
-                        if cur_char == 'z' {
-                            return Ok(Some(path_len + 1));
-                        } else {
-                            continue;
-                        }
-                    }
-
-                    let cur_char_int = cur_char as u32;
-                    let next_char_int = next_char as u32;
-
-                    if cur_char_int + 1 == next_char_int || next_char_int <= cur_char_int {
-                        q.push_back((new_row as usize, new_col as usize, path_len + 1));
-                        visited.insert((new_row as usize, new_col as usize));
-                    }
+        let mut visited = Grid::new(self.grid.width(), self.grid.height(), false);
+
+        q.push_back((start, 0));
+        visited[start] = true;
+
+        while let Some((pos, path_len)) = q.pop_front() {
+            let cur_height = Self::height(self.grid[pos]);
+
+            for next in self.grid.neighbors4(pos) {
+                if visited[next] || Self::height(self.grid[next]) > cur_height + 1 {
+                    continue;
+                }
+
+                if self.grid[next] == 'E' {
+                    return Ok(Some(path_len + 1));
                 }
+
+                q.push_back((next, path_len + 1));
+                visited[next] = true;
             }
         }
 
@@ -73,14 +72,12 @@ impl TopoMap {
     }
 
     fn find_start(&self, include_a: bool) -> Result<Vec<(usize, usize)>> {
-        let mut starting_locs = Vec::new();
-        for (row, line) in self.grid.iter().enumerate() {
-            for (col, loc) in line.iter().enumerate() {
-                if *loc == 'S' || (include_a && *loc == 'a') {
-                    starting_locs.push((row, col));
-                }
-            }
-        }
+        let starting_locs = self
+            .grid
+            .iter()
+            .filter(|(_, &loc)| loc == 'S' || (include_a && loc == 'a'))
+            .map(|(pos, _)| pos)
+            .collect::<Vec<_>>();
 
         if starting_locs.is_empty() {
             bail!("Start not found");
@@ -90,47 +87,74 @@ impl TopoMap {
     }
 }
 
-impl From<&str> for TopoMap {
-    fn from(input: &str) -> Self {
-        let mut grid = Vec::new();
-
-        for line in input.lines() {
-            let grid_line = line.chars().collect();
+impl TryFrom<&str> for TopoMap {
+    type Error = ParseError;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let grid = Grid::parse(input, "height", |c| {
+            (c.is_ascii_lowercase() || c == 'S' || c == 'E').then_some(c)
+        })?;
+
+        let mut markers = (0, 0);
+        for line in parse::lines(input) {
+            for (idx, c) in line.text.char_indices() {
+                let count = match c {
+                    'S' => &mut markers.0,
+                    'E' => &mut markers.1,
+                    _ => continue,
+                };
+
+                *count += 1;
+                if *count > 1 {
+                    return Err(line.error(&line.text[idx..=idx], "Duplicate marker"));
+                }
+            }
+        }
 
-            grid.push(grid_line);
+        if markers != (1, 1) {
+            return Err(ParseError::end_of_input(
+                grid.height(),
+                "Missing S or E marker",
+            ));
         }
 
-        Self { grid }
+        Ok(Self { grid })
     }
 }
 
-pub fn day_12() -> Result<()> {
-    day_12_1()?;
-    day_12_2()
-}
+/// [`Solution`] for day 12.
+pub struct Day12;
 
-fn day_12_1() -> Result<()> {
-    let topo_map = parse("input/day_12.txt")?;
-    let shortest_path = topo_map.find_shortest_path(false)?;
+impl Solution for Day12 {
+    const DAY: u32 = 12;
 
-    println!("Day 12-1: {}", shortest_path);
+    type Input = TopoMap;
 
-    Ok(())
-}
+    fn parse(input: &str) -> ParseResult<Self::Input> {
+        TopoMap::try_from(input)
+    }
 
-fn day_12_2() -> Result<()> {
-    let topo_map = parse("input/day_12.txt")?;
-    let shortest_path = topo_map.find_shortest_path(true)?;
+    fn part1(topo_map: &Self::Input) -> Result<Answer> {
+        day_12_1(topo_map)
+    }
 
-    println!("Day 12-1: {}", shortest_path);
+    fn part2(topo_map: &Self::Input) -> Result<Answer> {
+        day_12_2(topo_map)
+    }
+}
 
-    Ok(())
+/// Fewest steps from `S` to `E`.
+pub fn day_12_1(topo_map: &TopoMap) -> Result<Answer> {
+    let shortest_path = topo_map.find_shortest_path(false)?;
+
+    Ok(shortest_path.into())
 }
 
-fn parse(path: &str) -> Result<TopoMap> {
-    let input = std::fs::read_to_string(path)?;
+/// Fewest steps to `E` from any lowest square.
+pub fn day_12_2(topo_map: &TopoMap) -> Result<Answer> {
+    let shortest_path = topo_map.find_shortest_path(true)?;
 
-    Ok(TopoMap::from(input.as_str()))
+    Ok(shortest_path.into())
 }
 
 #[cfg(test)]
@@ -139,7 +163,8 @@ mod test {
 
     #[test]
     fn test_find_shortest_path() -> Result<()> {
-        let topo_map = parse("input/day_12_test.txt")?;
+        let input = std::fs::read_to_string("input/day_12_test.txt")?;
+        let topo_map = TopoMap::try_from(input.as_str())?;
 
         let shortest_path = topo_map.find_shortest_path(false)?;
 
@@ -150,7 +175,8 @@ mod test {
 
     #[test]
     fn test_find_shortest_path_include_a() -> Result<()> {
-        let topo_map = parse("input/day_12_test.txt")?;
+        let input = std::fs::read_to_string("input/day_12_test.txt")?;
+        let topo_map = TopoMap::try_from(input.as_str())?;
 
         let shortest_path = topo_map.find_shortest_path(true)?;
 
@@ -1,16 +1,25 @@
+//! Day 13: Distress Signal.
+
 use std::cmp::Ordering;
 
-use anyhow::{Error, Result};
-use itertools::Itertools;
+use anyhow::Result;
 use nom::branch::alt;
-use nom::character::complete::{char, line_ending, u32};
-use nom::multi::{separated_list0, separated_list1};
-use nom::sequence::{delimited, pair, separated_pair};
+use nom::character::complete::{char, u32};
+use nom::combinator::cut;
+use nom::multi::separated_list0;
+use nom::sequence::{preceded, terminated};
 use nom::Parser;
 
+use crate::answer::Answer;
+use crate::parse::{self, Line, ParseError, ParseResult};
+use crate::solution::Solution;
+
+/// A packet value. Ordered by the distress signal's comparison rules.
 #[derive(Debug, Clone)]
-enum Val {
+pub enum Val {
+    /// A list of values.
     Arr(Vec<Val>),
+    /// An integer.
     Int(u32),
 }
 
@@ -28,47 +37,58 @@ impl Eq for Val {}
 
 impl PartialOrd for Val {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let ordering = match (self, other) {
-            (Val::Int(a), Val::Int(b)) => a.cmp(b),
-            (Val::Int(a), Val::Arr(b)) => Val::Arr(vec![Val::Int(*a)]).cmp(&Val::Arr(b.clone())),
-            (Val::Arr(a), Val::Int(b)) => Val::Arr(a.clone()).cmp(&Val::Arr(vec![Val::Int(*b)])),
-            (Val::Arr(a), Val::Arr(b)) => a.cmp(b),
-        };
-
-        Some(ordering)
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for Val {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
+        match (self, other) {
+            (Val::Int(a), Val::Int(b)) => a.cmp(b),
+            (Val::Int(a), Val::Arr(b)) => Val::Arr(vec![Val::Int(*a)]).cmp(&Val::Arr(b.clone())),
+            (Val::Arr(a), Val::Int(b)) => Val::Arr(a.clone()).cmp(&Val::Arr(vec![Val::Int(*b)])),
+            (Val::Arr(a), Val::Arr(b)) => a.cmp(b),
+        }
     }
 }
 
-pub fn day_13() -> Result<()> {
-    day_13_1()?;
-    day_13_2()
-}
+/// [`Solution`] for day 13.
+pub struct Day13;
 
-fn day_13_1() -> Result<()> {
-    let input = std::fs::read_to_string("input/day_13.txt")?;
-    let result = sum_of_ordered_pairs(parse(&input)?);
+impl Solution for Day13 {
+    const DAY: u32 = 13;
 
-    println!("Day 13-1: {}", result);
+    type Input = Vec<(Val, Val)>;
 
-    Ok(())
+    fn parse(input: &str) -> ParseResult<Self::Input> {
+        parse(input)
+    }
+
+    fn part1(pairs: &Self::Input) -> Result<Answer> {
+        day_13_1(pairs)
+    }
+
+    fn part2(pairs: &Self::Input) -> Result<Answer> {
+        day_13_2(pairs)
+    }
 }
 
-fn day_13_2() -> Result<()> {
-    let input = std::fs::read_to_string("input/day_13.txt")?;
-    let result = decoder_key(parse(&input)?);
+/// Sum of the indices of pairs already in the right order.
+pub fn day_13_1(pairs: &[(Val, Val)]) -> Result<Answer> {
+    let result = sum_of_ordered_pairs(pairs);
 
-    println!("Day 13-2: {}", result);
+    Ok(result.into())
+}
 
-    Ok(())
+/// Decoder key for the sorted packets.
+pub fn day_13_2(pairs: &[(Val, Val)]) -> Result<Answer> {
+    let result = decoder_key(pairs);
+
+    Ok(result.into())
 }
 
-fn sum_of_ordered_pairs(pairs: Vec<(Val, Val)>) -> usize {
+/// Sum of the 1-based indices of pairs whose left packet sorts first.
+pub fn sum_of_ordered_pairs(pairs: &[(Val, Val)]) -> usize {
     pairs
         .iter()
         .enumerate()
@@ -76,46 +96,86 @@ fn sum_of_ordered_pairs(pairs: Vec<(Val, Val)>) -> usize {
         .sum::<usize>()
 }
 
-fn decoder_key(pairs: Vec<(Val, Val)>) -> usize {
+/// Product of the 1-based positions of the `[[2]]` and `[[6]]` divider packets once
+/// every packet is sorted.
+pub fn decoder_key(pairs: &[(Val, Val)]) -> usize {
     let packet_1 = Val::Arr(vec![Val::Arr(vec![Val::Int(2)])]);
     let packet_2 = Val::Arr(vec![Val::Arr(vec![Val::Int(6)])]);
 
-    let packets = pairs
-        .iter()
-        .flat_map(|(a, b)| [a, b])
-        .chain([&packet_1, &packet_2])
-        .sorted()
-        .collect::<Vec<_>>();
+    // A divider's position is one past every packet sorting before it. Counting rather than
+    // searching the sorted packets keeps packets that compare equal to a divider out of it
+    let packets = pairs.iter().flat_map(|(a, b)| [a, b]).collect::<Vec<_>>();
+    let position_1 = 1 + packets.iter().filter(|packet| ***packet < packet_1).count();
+    let position_2 = 2 + packets.iter().filter(|packet| ***packet < packet_2).count();
 
-    packets
-        .iter()
-        .enumerate()
-        .filter_map(|(idx, packet)| {
-            if **packet == packet_1 || **packet == packet_2 {
-                Some(idx + 1)
-            } else {
-                None
-            }
-        })
-        .product::<usize>()
+    position_1 * position_2
+}
+
+/// Deepest list nesting accepted. Parsing and comparing packets both recurse per level, so
+/// this keeps hostile input from overflowing the stack.
+const MAX_DEPTH: usize = 64;
+
+fn check_depth(line: &Line) -> ParseResult<()> {
+    let mut depth = 0usize;
+    for (idx, c) in line.text.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth = depth.saturating_sub(1),
+            _ => continue,
+        }
+
+        if depth > MAX_DEPTH {
+            return Err(line.error(
+                &line.text[idx..=idx],
+                format!("Packets can't nest more than {} lists deep", MAX_DEPTH),
+            ));
+        }
+    }
+
+    Ok(())
 }
 
 fn parse_val(input: &str) -> nom::IResult<&str, Val> {
     alt((
-        delimited(char('['), separated_list0(char(','), parse_val), char(']')).map(Val::Arr),
+        // Anything after a `[` has to be a list, so report errors inside it rather than at the `[`
+        preceded(
+            char('['),
+            cut(terminated(separated_list0(char(','), parse_val), char(']'))),
+        )
+        .map(Val::Arr),
         u32.map(Val::Int),
     ))(input)
 }
 
-fn parse(input: &str) -> Result<Vec<(Val, Val)>> {
-    let result = separated_list1(
-        pair(line_ending, line_ending),
-        separated_pair(parse_val, line_ending, parse_val),
-    )(input)
-    .map_err(|_| Error::msg("Failed to parse"))?
-    .1;
+/// Parses blank line separated pairs of packets.
+pub fn parse(input: &str) -> ParseResult<Vec<(Val, Val)>> {
+    let lines = parse::lines(input).collect::<Vec<_>>();
+
+    let mut pairs = Vec::new();
+    for group in lines.chunks(3) {
+        let [left, right] = match group {
+            [left, right, ..] => [left, right],
+            [left] => {
+                return Err(ParseError::end_of_input(
+                    left.number,
+                    "Missing second packet",
+                ))
+            }
+            _ => unreachable!(),
+        };
+
+        check_depth(left)?;
+        check_depth(right)?;
+        let left = left.nom(left.text, parse_val, "packet")?;
+        let right = right.nom(right.text, parse_val, "packet")?;
+        if let Some(blank) = group.get(2).filter(|line| !line.text.is_empty()) {
+            return Err(blank.error(blank.text, "Expected a blank line between pairs"));
+        }
 
-    Ok(result)
+        pairs.push((left, right));
+    }
+
+    Ok(pairs)
 }
 
 #[cfg(test)]
@@ -125,7 +185,7 @@ mod test {
     #[test]
     fn test_sum_of_ordered_pairs() -> Result<()> {
         let input = std::fs::read_to_string("input/day_13_test.txt")?;
-        let result = sum_of_ordered_pairs(parse(&input)?);
+        let result = sum_of_ordered_pairs(&parse(&input)?);
 
         assert_eq!(result, 13);
 
@@ -135,10 +195,28 @@ mod test {
     #[test]
     fn test_decoder_key() -> Result<()> {
         let input = std::fs::read_to_string("input/day_13_test.txt")?;
-        let result = decoder_key(parse(&input)?);
+        let result = decoder_key(&parse(&input)?);
 
         assert_eq!(result, 140);
 
         Ok(())
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("[1,[2]]\n[1,[x]]\n").unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (2, 5, "x]]")
+        );
+
+        let error = parse("[1]\n[2]\n\n[3]\n").unwrap_err();
+        assert_eq!((error.line, error.column), (5, 1));
+
+        let nested = format!("{}{}\n[]\n", "[".repeat(10000), "]".repeat(10000));
+        let error = parse(&nested).unwrap_err();
+        assert_eq!((error.line, error.column), (1, MAX_DEPTH + 1));
+        let deepest = format!("{}{}", "[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH));
+        assert!(parse(&format!("{}\n[]\n", deepest)).is_ok());
+    }
 }
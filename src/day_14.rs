@@ -1,15 +1,18 @@
+//! Day 14: Regolith Reservoir.
+
 use anyhow::Result;
 use nom::bytes::complete::tag;
-use nom::character::complete::{char, line_ending, u64};
-use nom::multi::{separated_list0, separated_list1};
+use nom::character::complete::{char, i64};
+use nom::combinator::consumed;
+use nom::multi::separated_list1;
 use nom::sequence::separated_pair;
-use tracing::debug;
+use tracing::{debug, instrument};
 
-#[derive(Debug)]
-struct Point {
-    x: usize,
-    y: usize,
-}
+use crate::answer::Answer;
+use crate::geometry::{Bounds, Coords, Point2};
+use crate::grid::Grid;
+use crate::parse::{self, ParseResult};
+use crate::solution::Solution;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum CavePoint {
@@ -28,30 +31,28 @@ impl From<CavePoint> for char {
     }
 }
 
-struct Cave {
-    grid: Vec<Vec<CavePoint>>,
+/// Slice of the cave: rock paths, settled sand and the floor two below the lowest rock.
+#[derive(Clone)]
+pub struct Cave {
+    grid: Grid<CavePoint>,
     floor: usize,
 }
 
 impl Cave {
-    const SAND_START: usize = 500;
-    const SAND_DIRS: [(isize, isize); 2] = [(-1, 1), (1, 1)];
+    const SAND_START: (usize, usize) = (500, 0);
+    /// Where sand tries to move, in order: straight down, then down-left, then down-right.
+    const SAND_DIRS: [(isize, isize); 3] = [(0, 1), (-1, 1), (1, 1)];
     const CAVE_WIDTH: usize = 800;
 
-    fn check_free_space(&self, x: isize, y: isize) -> bool {
-        if x >= 0 && x < self.grid.len() as isize && y >= 0 && y <= self.grid[0].len() as isize {
-            self.grid[x as usize][y as usize] == CavePoint::Air
-        } else {
-            false
-        }
-    }
-
-    fn drop_sand(&mut self, floor: bool) -> u64 {
+    /// Drops sand from `500,0` until it falls into the abyss, or with `floor` set, until the
+    /// source is blocked. Returns the units of sand that came to rest.
+    #[instrument(skip(self))]
+    pub fn drop_sand(&mut self, floor: bool) -> u64 {
         let mut counter = 0;
 
         if floor {
-            for x in 0..self.grid.len() {
-                self.grid[x][self.floor] = CavePoint::Rock;
+            for x in 0..self.grid.width() {
+                self.grid[(x, self.floor)] = CavePoint::Rock;
             }
         }
 
@@ -62,96 +63,59 @@ impl Cave {
         counter
     }
 
+    #[instrument(level = "debug", skip_all)]
     fn drop_sand_inner(&mut self) -> bool {
-        let mut cur_pos = Point {
-            x: Self::SAND_START,
-            y: 0,
-        };
+        let mut cur_pos = Self::SAND_START;
 
-        if self.grid[cur_pos.x][cur_pos.y] == CavePoint::Sand {
+        if self.grid[cur_pos] != CavePoint::Air {
             return false;
         }
 
-        let cave_depth = self.grid[0].len();
-        let mut resting = false;
-        while cur_pos.y < cave_depth {
-            let mut next_pos = Point {
-                x: cur_pos.x,
-                y: cur_pos.y + 1,
-            };
-
-            if next_pos.y >= cave_depth {
-                break;
+        loop {
+            // Anything falling past the bottom of the grid is lost to the abyss
+            if cur_pos.1 + 1 >= self.grid.height() {
+                return false;
             }
-            match self.grid[next_pos.x][next_pos.y] {
-                CavePoint::Rock | CavePoint::Sand => {
-                    let x_left = next_pos.x as isize + Self::SAND_DIRS[0].0;
-                    let y_left = next_pos.y as isize; // + Self::SAND_DIRS[0].1;
-                    let left = self.check_free_space(x_left, y_left);
-                    let x_right = next_pos.x as isize + Self::SAND_DIRS[1].0;
-                    let y_right = next_pos.y as isize; // + Self::SAND_DIRS[1].1;
-                    let right = self.check_free_space(x_right, y_right);
-
-                    if left {
-                        next_pos.x = x_left as usize;
-                        next_pos.y = y_left as usize;
-                    } else if right {
-                        next_pos.x = x_right as usize;
-                        next_pos.y = y_right as usize;
-                    } else {
-                        self.grid[cur_pos.x][cur_pos.y] = CavePoint::Sand;
-                        resting = true;
-                        break;
-                    }
+
+            let next_pos = Self::SAND_DIRS
+                .into_iter()
+                .filter_map(|dir| self.grid.offset(cur_pos, dir))
+                .find(|next_pos| self.grid[*next_pos] == CavePoint::Air);
+
+            match next_pos {
+                Some(next_pos) => cur_pos = next_pos,
+                None => {
+                    self.grid[cur_pos] = CavePoint::Sand;
+                    return true;
                 }
-                CavePoint::Air => {}
             }
-
-            cur_pos = next_pos;
         }
-
-        resting
     }
 }
 
 impl std::fmt::Debug for Cave {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in 0..self.grid.len() {
-            for x in 0..self.grid[0].len() {
-                write!(f, "{}", char::from(self.grid[x][y]))?;
-            }
-            writeln!(f)?;
-        }
-
-        Ok(())
+        write!(f, "{}", self.grid.render(|point| char::from(*point)))
     }
 }
 
-impl From<Vec<Vec<Point>>> for Cave {
-    fn from(lines: Vec<Vec<Point>>) -> Self {
-        let mut grid = vec![vec![CavePoint::Air; Self::CAVE_WIDTH]; Self::CAVE_WIDTH];
+// Paths are checked to lie inside the cave while parsing
+impl From<Vec<Vec<Point2>>> for Cave {
+    fn from(lines: Vec<Vec<Point2>>) -> Self {
+        let cell = |point: Point2| (point.x as usize, point.y as usize);
+
+        let mut grid = Grid::new(Self::CAVE_WIDTH, Self::CAVE_WIDTH, CavePoint::Air);
         let mut floor = 0;
         for line in lines {
             for window in line.windows(2) {
-                if window[0].x != window[1].x {
-                    let x_start = std::cmp::min(window[0].x, window[1].x);
-                    let x_end = std::cmp::max(window[0].x, window[1].x);
-                    let y = window[0].y;
-                    floor = std::cmp::max(y, floor);
-                    for x in x_start..(x_end + 1) {
-                        grid[x][y] = CavePoint::Rock;
-                    }
+                let (mut point, end) = (window[0], window[1]);
+                grid[cell(point)] = CavePoint::Rock;
+                while point != end {
+                    point = point.step_toward(end);
+                    grid[cell(point)] = CavePoint::Rock;
                 }
 
-                if window[0].y != window[1].y {
-                    let y_start = std::cmp::min(window[0].y, window[1].y);
-                    let y_end = std::cmp::max(window[0].y, window[1].y);
-                    let x = window[0].x;
-                    for y in y_start..(y_end + 1) {
-                        floor = std::cmp::max(y, floor);
-                        grid[x][y] = CavePoint::Rock;
-                    }
-                }
+                floor = floor.max(window[0].y.max(end.y) as usize);
             }
         }
 
@@ -160,53 +124,86 @@ impl From<Vec<Vec<Point>>> for Cave {
     }
 }
 
-fn parse_val(input: &str) -> nom::IResult<&str, Point> {
-    let (i, (x, y)) = separated_pair(u64, char(','), u64)(input)?;
+fn parse_val(input: &str) -> nom::IResult<&str, Point2> {
+    let (i, (x, y)) = separated_pair(i64, char(','), i64)(input)?;
 
-    Ok((
-        i,
-        Point {
-            x: x as usize,
-            y: y as usize,
-        },
-    ))
+    Ok((i, Point2::new(x, y)))
 }
 
-fn parse_line(input: &str) -> nom::IResult<&str, Vec<Point>> {
-    separated_list0(tag(" -> "), parse_val)(input)
+fn parse_line(input: &str) -> nom::IResult<&str, Vec<(&str, Point2)>> {
+    separated_list1(tag(" -> "), consumed(parse_val))(input)
 }
 
-fn parse(path: &str) -> Result<Cave> {
-    let input = std::fs::read_to_string(path)?;
-    let result = separated_list1(line_ending, parse_line)(&input).unwrap();
-    let cave = Cave::from(result.1);
+/// Parses rock paths of `x,y -> x,y` points, one per line.
+pub fn parse(input: &str) -> ParseResult<Cave> {
+    let mut paths = Vec::new();
+
+    for line in parse::lines(input).filter(|line| !line.text.is_empty()) {
+        let path = line.nom(line.text, parse_line, "rock path")?;
 
-    Ok(cave)
+        // Leave room below the lowest rock for the floor
+        let cave = Bounds {
+            min: Point2::ORIGIN,
+            max: Point2::new(Cave::CAVE_WIDTH as i64 - 1, Cave::CAVE_WIDTH as i64 - 3),
+        };
+        for (token, point) in &path {
+            if !cave.contains(*point) {
+                return Err(line.error(token, "Point outside the cave"));
+            }
+        }
+
+        for window in path.windows(2) {
+            let ((_, start), (end_token, end)) = (&window[0], &window[1]);
+            if start.x != end.x && start.y != end.y {
+                return Err(line.error(end_token, "Rock paths must be horizontal or vertical"));
+            }
+        }
+
+        paths.push(path.into_iter().map(|(_, point)| point).collect());
+    }
+
+    Ok(Cave::from(paths))
 }
 
-pub fn day_14() -> Result<()> {
-    day_14_1()?;
-    day_14_2()
+/// [`Solution`] for day 14.
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u32 = 14;
+
+    type Input = Cave;
+
+    fn parse(input: &str) -> ParseResult<Self::Input> {
+        parse(input)
+    }
+
+    fn part1(cave: &Self::Input) -> Result<Answer> {
+        day_14_1(cave)
+    }
+
+    fn part2(cave: &Self::Input) -> Result<Answer> {
+        day_14_2(cave)
+    }
 }
 
-fn day_14_1() -> Result<()> {
-    let mut cave = parse("input/day_14.txt")?;
+/// Units of sand that rest before sand falls into the abyss.
+pub fn day_14_1(cave: &Cave) -> Result<Answer> {
+    let mut cave = cave.clone();
     let sand = cave.drop_sand(false);
 
-    println!("Day 14-1: {}", sand);
     debug!("{:?}", cave);
 
-    Ok(())
+    Ok(sand.into())
 }
 
-fn day_14_2() -> Result<()> {
-    let mut cave = parse("input/day_14.txt")?;
+/// Units of sand that rest before the source is blocked.
+pub fn day_14_2(cave: &Cave) -> Result<Answer> {
+    let mut cave = cave.clone();
     let sand = cave.drop_sand(true);
 
-    println!("Day 14-2: {}", sand);
     debug!("{:?}", cave);
 
-    Ok(())
+    Ok(sand.into())
 }
 
 #[cfg(test)]
@@ -215,7 +212,8 @@ mod test {
 
     #[test]
     fn test_part_1() -> Result<()> {
-        let mut cave = parse("input/day_14_test.txt")?;
+        let input = std::fs::read_to_string("input/day_14_test.txt")?;
+        let mut cave = parse(&input)?;
 
         let sand = cave.drop_sand(false);
 
@@ -1,38 +1,88 @@
+//! Day 15: Beacon Exclusion Zone.
+
 use std::collections::HashSet;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+
+use crate::answer::Answer;
+use crate::geometry::{Bounds, Coords, Point2};
+use crate::parse::{self, Line, ParseResult};
+use crate::solution::Solution;
+
+/// Largest coordinate in the example, give or take. Anything further out is a real input.
+const EXAMPLE_EXTENT: i64 = 100;
+
+/// Where to look: the row part 1 counts and the far corner of the square part 2 searches from
+/// the origin. The example and the real puzzle use different ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scan {
+    /// Row to count covered positions on.
+    pub row: i64,
+    /// Largest x and y the distress beacon can have.
+    pub search_max: i64,
+}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Point {
-    x: i64,
-    y: i64,
+impl Scan {
+    /// The example's row 10 and 0..=20 search area.
+    pub const EXAMPLE: Self = Self {
+        row: 10,
+        search_max: 20,
+    };
+    /// The real puzzle's row 2000000 and 0..=4000000 search area.
+    pub const PUZZLE: Self = Self {
+        row: 2_000_000,
+        search_max: 4_000_000,
+    };
+
+    /// The scan `pairs` were written for, going by how far out their coordinates reach.
+    pub fn for_input(pairs: &[SensorPair]) -> Self {
+        let extent = pairs
+            .iter()
+            .flat_map(|pair| [pair.sensor, pair.beacon])
+            .map(|point| point.x.abs().max(point.y.abs()))
+            .max()
+            .unwrap_or(0);
+
+        if extent <= EXAMPLE_EXTENT {
+            Self::EXAMPLE
+        } else {
+            Self::PUZZLE
+        }
+    }
 }
 
+/// A sensor and the closest beacon it detects.
 #[derive(Debug, PartialEq, Eq, Hash)]
-struct SensorPair {
-    sensor: Point,
-    beacon: Point,
+pub struct SensorPair {
+    sensor: Point2,
+    beacon: Point2,
     distance: i64,
 }
 
 impl SensorPair {
-    fn check_point_in_range(&self, point: Point) -> bool {
-        taxi_cab_distance(self.sensor, point) <= self.distance
+    fn check_point_in_range(&self, point: Point2) -> bool {
+        self.sensor.manhattan(point) <= self.distance
     }
 }
 
-fn max_x(sensors: &[SensorPair]) -> i64 {
-    sensors.iter().map(|pair| pair.sensor.x).max().unwrap()
+/// Smallest box holding every position some sensor covers.
+fn coverage(pairs: &[SensorPair]) -> Option<Bounds<Point2>> {
+    Bounds::from_points(pairs.iter().flat_map(|pair| {
+        let reach = Point2::new(pair.distance, pair.distance);
+        [pair.sensor - reach, pair.sensor + reach]
+    }))
 }
 
-fn check_at_y(pairs: &Vec<SensorPair>, y: i64) -> u64 {
-    let max_x = max_x(pairs) * 2;
-    let min_x = -max_x;
+/// Number of positions on row `y` where a beacon can't be.
+pub fn check_at_y(pairs: &[SensorPair], y: i64) -> u64 {
+    let Some(coverage) = coverage(pairs) else {
+        return 0;
+    };
     let mut count = 0;
-    let beacon_set: HashSet<Point> = pairs.iter().map(|pair| pair.beacon).collect();
+    let beacon_set: HashSet<Point2> = pairs.iter().map(|pair| pair.beacon).collect();
 
-    for x in min_x..max_x {
-        let point = Point { x, y };
+    for x in coverage.min.x..=coverage.max.x {
+        let point = Point2::new(x, y);
         if beacon_set.contains(&point) {
             continue;
         }
@@ -48,23 +98,28 @@ fn check_at_y(pairs: &Vec<SensorPair>, y: i64) -> u64 {
     count
 }
 
-fn parse_point(token: &str) -> Result<Point> {
-    let tokens = token.split('=').collect::<Vec<_>>();
-    let x = tokens[1].split(", ").collect::<Vec<_>>()[0].parse::<i64>()?;
-    let y = tokens[2].parse::<i64>()?;
+fn parse_point(line: &Line, token: &str) -> ParseResult<Point2> {
+    let (_, coords) = token
+        .split_once("x=")
+        .ok_or_else(|| line.error(token, "Expected a position like x=2, y=18"))?;
+    let [x, y] = line.split(coords, ", y=")?;
+
+    // Positions fit in 32 bits so distances between them can't overflow
+    let x = line.parse::<i32>(x, "x position")?;
+    let y = line.parse::<i32>(y, "y position")?;
 
-    Ok(Point { x, y })
+    Ok(Point2::new(x.into(), y.into()))
 }
 
-fn parse(path: &str) -> Result<Vec<SensorPair>> {
-    let input = std::fs::read_to_string(path)?;
+/// Parses one sensor and beacon report per line.
+pub fn parse(input: &str) -> ParseResult<Vec<SensorPair>> {
     let mut sensors = Vec::new();
-    for line in input.lines() {
-        let tokens = line.split(':').collect::<Vec<_>>();
-        let sensor = parse_point(tokens[0])?;
-        let beacon = parse_point(tokens[1])?;
+    for line in parse::lines(input) {
+        let [sensor, beacon] = line.tokens(":")?;
+        let sensor = parse_point(&line, sensor)?;
+        let beacon = parse_point(&line, beacon)?;
 
-        let distance = taxi_cab_distance(sensor, beacon);
+        let distance = sensor.manhattan(beacon);
 
         sensors.push(SensorPair {
             sensor,
@@ -76,59 +131,104 @@ fn parse(path: &str) -> Result<Vec<SensorPair>> {
     Ok(sensors)
 }
 
-fn taxi_cab_distance(point1: Point, point2: Point) -> i64 {
-    (point1.x - point2.x).abs() + (point1.y - point2.y).abs()
-}
+/// Every point just outside the sensor's range, walked one quadrant at a time.
+fn perimeter(pair: &SensorPair) -> impl Iterator<Item = Point2> + '_ {
+    let possible_dist = pair.distance + 1;
 
-fn build_zone_set(pairs: &Vec<SensorPair>) -> HashSet<Point> {
-    let mut set = HashSet::new();
-    let dirs = [(1, 1), (-1, 1), (1, -1), (-1, -1)];
-    for pair in pairs {
-        let possible_dist = pair.distance + 1;
-        for i in 0..(possible_dist + 1) {
-            for (x_sign, y_sign) in dirs {
-                let x = pair.sensor.x + i * x_sign;
-                let y = pair.sensor.y + possible_dist - i * y_sign;
-                set.insert(Point { x, y });
-            }
-        }
-    }
-
-    set
+    (0..=possible_dist).flat_map(move |i| {
+        Point2::DIAGONAL.into_iter().map(move |dir| {
+            let offset = Point2::new(i * dir.x, (possible_dist - i) * dir.y);
+            pair.sensor + offset
+        })
+    })
 }
 
-fn tuning_frequency(zone_set: HashSet<Point>, pairs: &[SensorPair]) -> i64 {
-    let ret_point = zone_set
+fn tuning_frequency(pairs: &[SensorPair], search_max: i64) -> Result<i64> {
+    let search_area = Bounds {
+        min: Point2::ORIGIN,
+        max: Point2::new(search_max, search_max),
+    };
+    // The only uncovered spot borders some sensor's range, so only perimeters are checked.
+    // Perimeters of real inputs run to millions of points, so they're walked rather than
+    // collected
+    let ret_point = pairs
         .iter()
-        .filter(|point| !pairs.iter().all(|pair| pair.check_point_in_range(**point)))
+        .flat_map(perimeter)
+        .filter(|point| search_area.contains(*point))
+        .filter(|point| !pairs.iter().any(|pair| pair.check_point_in_range(*point)))
+        .collect::<HashSet<_>>()
+        .into_iter()
         .collect::<Vec<_>>();
 
-    // assert_eq!(ret_point.len(), 1);
+    if ret_point.len() != 1 {
+        bail!(
+            "Expected one spot for the beacon, found {}",
+            ret_point.len()
+        );
+    }
 
-    ret_point[0].x * 4000000 + ret_point[0].y
+    Ok(ret_point[0].x * 4000000 + ret_point[0].y)
 }
 
-pub fn day_15() -> Result<()> {
-    day_15_1()?;
-    day_15_2()
+/// [`Solution`] for day 15.
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u32 = 15;
+
+    type Input = Vec<SensorPair>;
+
+    fn parse(input: &str) -> ParseResult<Self::Input> {
+        parse(input)
+    }
+
+    fn part1(pairs: &Self::Input) -> Result<Answer> {
+        day_15_1(pairs)
+    }
+
+    fn part2(pairs: &Self::Input) -> Result<Answer> {
+        day_15_2(pairs)
+    }
 }
 
-fn day_15_1() -> Result<()> {
-    let pairs = parse("input/day_15_test.txt")?;
+/// Positions on the [`Scan`]'s row where a beacon can't be.
+pub fn day_15_1(pairs: &[SensorPair]) -> Result<Answer> {
+    let count = check_at_y(pairs, Scan::for_input(pairs).row);
 
-    let count = check_at_y(&pairs, 10);
-    println!("Day 15-1: {}", count);
+    Ok(count.into())
+}
+
+/// Tuning frequency of the distress beacon.
+pub fn day_15_2(pairs: &[SensorPair]) -> Result<Answer> {
+    let freq = tuning_frequency(pairs, Scan::for_input(pairs).search_max)?;
 
-    Ok(())
+    Ok(freq.into())
 }
 
-fn day_15_2() -> Result<()> {
-    let pairs = parse("input/day_15_test.txt")?;
+#[cfg(test)]
+mod test {
+    use super::*;
 
-    let zone_set = build_zone_set(&pairs);
-    let freq = tuning_frequency(zone_set, &pairs);
+    #[test]
+    fn test_scan_for_input() -> Result<()> {
+        let example = parse(&std::fs::read_to_string("input/day_15_test.txt")?)?;
+        assert_eq!(Scan::for_input(&example), Scan::EXAMPLE);
 
-    println!("Day 15-2: {}", freq);
+        let real =
+            parse("Sensor at x=3999724, y=2000469: closest beacon is at x=4281123, y=2282046")?;
+        assert_eq!(Scan::for_input(&real), Scan::PUZZLE);
+
+        Ok(())
+    }
 
-    Ok(())
+    #[test]
+    fn test_tuning_frequency() -> Result<()> {
+        let pairs = parse(&std::fs::read_to_string("input/day_15_test.txt")?)?;
+        assert_eq!(tuning_frequency(&pairs, 20)?, 56000011);
+
+        // The whole search area is covered once it shrinks past the beacon's spot
+        assert!(tuning_frequency(&pairs, 10).is_err());
+
+        Ok(())
+    }
 }
@@ -1,18 +1,36 @@
+//! Day 16: Proboscidea Volcanium.
+
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 use anyhow::Result;
 
-pub fn day_16() -> Result<()> {
-    day_16_1()
-}
+use crate::answer::Answer;
+use crate::parse::{self, ParseError, ParseResult};
+use crate::solution::Solution;
+
+/// [`Solution`] for day 16.
+pub struct Day16;
+
+impl Solution for Day16 {
+    const DAY: u32 = 16;
+    const PARTS: u8 = 1;
 
-fn day_16_1() -> Result<()> {
-    let volcano = parse("input/day_16.txt")?;
+    type Input = Volcano;
+
+    fn parse(input: &str) -> ParseResult<Self::Input> {
+        parse(input)
+    }
+
+    fn part1(volcano: &Self::Input) -> Result<Answer> {
+        day_16_1(volcano)
+    }
+}
 
+/// Most pressure that can be released in 30 minutes.
+pub fn day_16_1(volcano: &Volcano) -> Result<Answer> {
     let pressure = volcano.find_highest_pressure(30);
-    println!("Day 16-1: {}", pressure);
 
-    Ok(())
+    Ok(pressure.into())
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -39,15 +57,17 @@ struct Valve {
     tunnels: Vec<String>,
 }
 
+/// The valves and the tunnels between them.
 #[derive(Debug)]
-struct Volcano {
+pub struct Volcano {
     valve_map: HashMap<String, Valve>,
 }
 
 impl Volcano {
-    fn find_highest_pressure(&self, time: u32) -> u32 {
+    /// Most pressure that can be released in `time` minutes starting at valve `AA`.
+    pub fn find_highest_pressure(&self, time: u32) -> u32 {
         let mut valve_stack = vec![];
-        let cur_valve = self.valve_map.get(&"AA".to_string()).unwrap();
+        let cur_valve = self.valve_map.get("AA").unwrap();
         let dp = Rc::new(RefCell::new(HashMap::new()));
 
         self.find_highest_pressure_inner(0, &mut valve_stack, time, cur_valve, dp)
@@ -125,26 +145,35 @@ impl Volcano {
     }
 }
 
-fn parse(path: &str) -> Result<Volcano> {
-    let input = std::fs::read_to_string(path)?;
+/// Parses one valve report per line.
+pub fn parse(input: &str) -> ParseResult<Volcano> {
     let mut valve_map = HashMap::new();
+    let mut tunnel_ends = Vec::new();
+
+    let mut line_count = 0;
+    for line in parse::lines(input) {
+        line_count = line.number;
 
-    for line in input.lines() {
-        let tokens = line.split("; ").collect::<Vec<_>>();
-        let rate_tokens = tokens[0].split(' ').collect::<Vec<_>>();
-        let name = rate_tokens[1].to_string();
-        let flow_rate = rate_tokens[4]
-            .split('=')
-            .nth(1)
-            .expect("Rate value doesn't exist")
-            .parse::<u32>()?;
+        let [valve, tunnel_list] = line.tokens("; ")?;
+        let [_, name, _, _, rate] = line.split(valve, " ")?;
+        let rate = rate
+            .strip_prefix("rate=")
+            .ok_or_else(|| line.error(rate, "Expected rate=N"))?;
+        let flow_rate = line.parse::<u32>(rate, "flow rate")?;
+
+        let tunnel_list = tunnel_list
+            .strip_prefix("tunnels lead to valves ")
+            .or_else(|| tunnel_list.strip_prefix("tunnel leads to valve "))
+            .ok_or_else(|| line.error(tunnel_list, "Expected a list of tunnels"))?;
 
         let mut tunnels = Vec::new();
-        let tunnel_tokens = tokens[1].split(' ').collect::<Vec<_>>();
-        for token in tunnel_tokens[4..].iter() {
-            tunnels.push(token.replace(',', ""));
+        for token in tunnel_list.split(", ") {
+            tunnel_ends.push((line, token));
+            tunnels.push(token.to_string());
         }
 
+        let name = name.to_string();
+
         valve_map.insert(
             name.clone(),
             Valve {
@@ -155,6 +184,21 @@ fn parse(path: &str) -> Result<Volcano> {
         );
     }
 
+    if !valve_map.contains_key("AA") {
+        return Err(ParseError::end_of_input(
+            line_count,
+            "Missing starting valve AA",
+        ));
+    }
+
+    // Tunnels can lead to valves described further down
+    if let Some((line, token)) = tunnel_ends
+        .iter()
+        .find(|(_, token)| !valve_map.contains_key(*token))
+    {
+        return Err(line.error(token, "No such valve"));
+    }
+
     Ok(Volcano { valve_map })
 }
 
@@ -164,7 +208,8 @@ mod test {
 
     #[test]
     fn test_valve_backtrack() -> Result<()> {
-        let volcano = parse("input/day_16_test.txt")?;
+        let input = std::fs::read_to_string("input/day_16_test.txt")?;
+        let volcano = parse(&input)?;
 
         let pressure = volcano.find_highest_pressure(30);
 
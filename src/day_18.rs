@@ -1,79 +1,70 @@
-use std::{collections::HashSet, ops::Add};
+//! Day 18: Boiling Boulders.
 
-use anyhow::Result;
-
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-struct Cube {
-    x: i64,
-    y: i64,
-    z: i64,
-}
-
-impl Cube {
-    const DIRS: [(i64, i64, i64); 6] = [
-        (1, 0, 0),
-        (-1, 0, 0),
-        (0, 1, 0),
-        (0, -1, 0),
-        (0, 0, 1),
-        (0, 0, -1),
-    ];
-
-    fn adjacent(&self) -> impl Iterator<Item = Cube> + '_ {
-        Self::DIRS.iter().map(|dir| *self + *dir)
-    }
-}
+use std::collections::HashSet;
 
-impl Add<(i64, i64, i64)> for Cube {
-    type Output = Cube;
+use anyhow::Result;
 
-    fn add(self, rhs: (i64, i64, i64)) -> Self::Output {
-        Cube {
-            x: self.x + rhs.0,
-            y: self.y + rhs.1,
-            z: self.z + rhs.2,
-        }
-    }
-}
+use crate::answer::Answer;
+use crate::geometry::Point3;
+use crate::parse::{self, ParseResult};
+use crate::solution::Solution;
 
-struct LavaFlow {
-    cubes: HashSet<Cube>,
+/// The scanned lava droplet as a set of unit cubes.
+pub struct LavaFlow {
+    cubes: HashSet<Point3>,
 }
 
 impl LavaFlow {
-    fn calculate_surface_area(&self) -> usize {
+    /// Number of cube faces not touching another cube.
+    pub fn calculate_surface_area(&self) -> usize {
         self.cubes
             .iter()
-            .flat_map(|cube| cube.adjacent())
+            .flat_map(|cube| cube.neighbors6())
             .filter(|cube| !self.cubes.contains(cube))
             .count()
     }
 }
 
-fn parse(path: &str) -> Result<LavaFlow> {
-    let input = std::fs::read_to_string(path)?;
-
+/// Parses one `x,y,z` cube per line.
+pub fn parse(input: &str) -> ParseResult<LavaFlow> {
     let mut cubes = HashSet::new();
-    for line in input.lines() {
-        let tokens = line.split(',').collect::<Vec<_>>();
+    for line in parse::lines(input) {
+        let [x, y, z] = line.tokens(",")?;
 
-        let x = tokens[0].parse::<i64>()?;
-        let y = tokens[1].parse::<i64>()?;
-        let z = tokens[2].parse::<i64>()?;
+        // Positions fit in 32 bits so stepping to a neighbour can't overflow
+        let x = line.parse::<i32>(x, "x position")?;
+        let y = line.parse::<i32>(y, "y position")?;
+        let z = line.parse::<i32>(z, "z position")?;
 
-        cubes.insert(Cube { x, y, z });
+        cubes.insert(Point3::new(x.into(), y.into(), z.into()));
     }
 
     Ok(LavaFlow { cubes })
 }
 
-pub fn day_18() -> Result<()> {
-    let lava_flow = parse("input/day_18.txt")?;
-    let surface_area = lava_flow.calculate_surface_area();
+/// [`Solution`] for day 18.
+pub struct Day18;
+
+impl Solution for Day18 {
+    const DAY: u32 = 18;
+    const PARTS: u8 = 1;
+
+    type Input = LavaFlow;
+
+    fn parse(input: &str) -> ParseResult<Self::Input> {
+        parse(input)
+    }
 
-    println!("Day 18-1: {}", surface_area);
+    fn part1(lava_flow: &Self::Input) -> Result<Answer> {
+        day_18_1(lava_flow)
+    }
+}
+
+/// Surface area of the droplet.
+pub fn day_18_1(lava_flow: &LavaFlow) -> Result<Answer> {
+    let surface_area = lava_flow.calculate_surface_area();
 
-    Ok(())
+    Ok(surface_area.into())
 }
 
 #[cfg(test)]
@@ -82,7 +73,8 @@ mod test {
 
     #[test]
     fn test_cube_surface() -> Result<()> {
-        let lava_flow = parse("input/day_18_test.txt")?;
+        let input = std::fs::read_to_string("input/day_18_test.txt")?;
+        let lava_flow = parse(&input)?;
 
         let surface_area = lava_flow.calculate_surface_area();
 
@@ -1,163 +1,746 @@
-use std::cmp::Ordering;
-use std::fs::File;
-use std::io::BufRead;
+//! Day 2: Rock Paper Scissors.
+//!
+//! Guides are scored by a [`Game`], which can be any cyclic hand game with an odd number of
+//! shapes. The puzzle plays [`Game::rps`].
+
+use std::collections::HashMap;
+use std::fmt::Write;
 
 use anyhow::{bail, Error, Result};
 
-#[derive(PartialEq, Eq)]
-enum Rps {
-    Rock,
-    Paper,
-    Scissors,
+use crate::answer::Answer;
+use crate::parse::{self, ParseResult};
+use crate::solution::Solution;
+
+/// A shape in a [`Game`], by its position in the game's cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Shape(usize);
+
+/// Result of a round from our point of view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchOutcome {
+    /// We won.
+    Win,
+    /// Both played the same shape.
+    Draw,
+    /// We lost.
+    Loss,
+}
+
+/// Points for each outcome of a round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutcomeScores {
+    /// Points for a loss.
+    pub loss: u64,
+    /// Points for a draw.
+    pub draw: u64,
+    /// Points for a win.
+    pub win: u64,
 }
 
-impl Rps {
-    fn value(&self) -> u64 {
-        match self {
-            Rps::Rock => 1,
-            Rps::Paper => 2,
-            Rps::Scissors => 3,
+impl OutcomeScores {
+    /// Points for `outcome`.
+    pub fn score(&self, outcome: MatchOutcome) -> u64 {
+        match outcome {
+            MatchOutcome::Win => self.win,
+            MatchOutcome::Draw => self.draw,
+            MatchOutcome::Loss => self.loss,
         }
     }
+}
 
-    fn losing_play(&self) -> Self {
-        match self {
-            Rps::Rock => Rps::Scissors,
-            Rps::Paper => Rps::Rock,
-            Rps::Scissors => Rps::Paper,
+/// The puzzle's 0, 3 and 6 points.
+impl Default for OutcomeScores {
+    fn default() -> Self {
+        Self {
+            loss: 0,
+            draw: 3,
+            win: 6,
         }
     }
+}
 
-    fn winning_play(&self) -> Self {
-        match self {
-            Rps::Rock => Rps::Paper,
-            Rps::Paper => Rps::Scissors,
-            Rps::Scissors => Rps::Rock,
+/// A cyclic hand game, along with how a strategy guide for it is written and scored.
+///
+/// Shapes are listed in cycle order: each one beats the `(n - 1) / 2` shapes before it,
+/// wrapping around, and loses to the ones after it.
+#[derive(Debug, Clone)]
+pub struct Game {
+    shapes: Vec<(String, u64)>,
+    outcome_scores: OutcomeScores,
+    opponent_symbols: HashMap<String, Shape>,
+    response_symbols: HashMap<String, Shape>,
+    outcome_symbols: HashMap<String, MatchOutcome>,
+}
+
+impl Game {
+    /// Game over `shapes`, given in cycle order with the points for playing each. Fails unless
+    /// there's an odd number of at least 3 distinct shapes. Guide symbols are added with
+    /// [`Game::opponent_symbol`], [`Game::response_symbol`] and [`Game::outcome_symbol`].
+    pub fn new(shapes: &[(&str, u64)], outcome_scores: OutcomeScores) -> Result<Self> {
+        if shapes.len() < 3 || shapes.len().is_multiple_of(2) {
+            bail!(
+                "Cyclic games need an odd number of at least 3 shapes, got {}",
+                shapes.len()
+            );
+        }
+        for (idx, (name, _)) in shapes.iter().enumerate() {
+            if shapes[..idx].iter().any(|(other, _)| other == name) {
+                bail!("Duplicate shape: {}", name);
+            }
         }
+
+        let most = shapes.iter().map(|(_, score)| *score).max().unwrap_or(0);
+        let most_outcome = outcome_scores
+            .loss
+            .max(outcome_scores.draw)
+            .max(outcome_scores.win);
+        if most.checked_add(most_outcome).is_none() {
+            bail!("Round scores don't fit in 64 bits");
+        }
+
+        Ok(Self {
+            shapes: shapes
+                .iter()
+                .map(|(name, score)| (name.to_string(), *score))
+                .collect(),
+            outcome_scores,
+            opponent_symbols: HashMap::new(),
+            response_symbols: HashMap::new(),
+            outcome_symbols: HashMap::new(),
+        })
     }
-}
 
-impl TryFrom<&str> for Rps {
-    type Error = Error;
+    /// Rock, paper, scissors as the puzzle plays it. The opponent plays `A`, `B` and `C` and
+    /// the second column is `X`, `Y` and `Z`, read either as the same shapes or as losing,
+    /// drawing and winning. Shapes score 1, 2 and 3.
+    pub fn rps() -> Self {
+        Self::preset(&[("Rock", 1), ("Paper", 2), ("Scissors", 3)], "ABC", "XYZ")
+    }
+
+    /// Rock, paper, scissors, lizard, Spock. The opponent plays `A` to `E` and the second
+    /// column's `V` to `Z` are the same shapes, scoring 1 to 5 in that order. `X`, `Y` and `Z`
+    /// also stand for losing, drawing and winning.
+    pub fn rpsls() -> Self {
+        // Cycle order, so each shape beats the two before it
+        let shapes = [
+            ("Rock", 1),
+            ("Spock", 5),
+            ("Paper", 2),
+            ("Lizard", 4),
+            ("Scissors", 3),
+        ];
+        Self::preset(&shapes, "AEBDC", "VZWYX")
+    }
+
+    /// Game with the puzzle's scores and outcome symbols, and one opponent and response
+    /// symbol per shape, in cycle order.
+    fn preset(shapes: &[(&str, u64)], opponent: &str, response: &str) -> Self {
+        let symbols = |symbols: &str| {
+            symbols
+                .chars()
+                .enumerate()
+                .map(|(idx, symbol)| (symbol.to_string(), Shape(idx)))
+                .collect()
+        };
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
-            "A" | "X" => Ok(Rps::Rock),
-            "B" | "Y" => Ok(Rps::Paper),
-            "C" | "Z" => Ok(Rps::Scissors),
-            _ => bail!("Invalid RPS Option: {}", value),
+        Self {
+            shapes: shapes
+                .iter()
+                .map(|(name, score)| (name.to_string(), *score))
+                .collect(),
+            outcome_scores: OutcomeScores::default(),
+            opponent_symbols: symbols(opponent),
+            response_symbols: symbols(response),
+            outcome_symbols: [
+                ("X", MatchOutcome::Loss),
+                ("Y", MatchOutcome::Draw),
+                ("Z", MatchOutcome::Win),
+            ]
+            .into_iter()
+            .map(|(symbol, outcome)| (symbol.to_string(), outcome))
+            .collect(),
         }
     }
-}
 
-impl PartialOrd for Rps {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        match (self, other) {
-            (Rps::Rock, Rps::Rock) => Some(Ordering::Equal),
-            (Rps::Rock, Rps::Paper) => Some(Ordering::Less),
-            (Rps::Rock, Rps::Scissors) => Some(Ordering::Greater),
-            (Rps::Paper, Rps::Rock) => Some(Ordering::Greater),
-            (Rps::Paper, Rps::Paper) => Some(Ordering::Equal),
-            (Rps::Paper, Rps::Scissors) => Some(Ordering::Less),
-            (Rps::Scissors, Rps::Rock) => Some(Ordering::Less),
-            (Rps::Scissors, Rps::Paper) => Some(Ordering::Greater),
-            (Rps::Scissors, Rps::Scissors) => Some(Ordering::Equal),
+    /// The shape called `name`.
+    pub fn shape(&self, name: &str) -> Result<Shape> {
+        self.shapes
+            .iter()
+            .position(|(shape, _)| shape == name)
+            .map(Shape)
+            .ok_or_else(|| Error::msg(format!("No such shape: {}", name)))
+    }
+
+    /// Reads `symbol` in the guide's first column as the opponent playing `shape`.
+    pub fn opponent_symbol(mut self, symbol: &str, shape: &str) -> Result<Self> {
+        let shape = self.shape(shape)?;
+        self.opponent_symbols.insert(symbol.to_string(), shape);
+
+        Ok(self)
+    }
+
+    /// Reads `symbol` in the guide's second column as us playing `shape`, for
+    /// [`Game::score_shapes`].
+    pub fn response_symbol(mut self, symbol: &str, shape: &str) -> Result<Self> {
+        let shape = self.shape(shape)?;
+        self.response_symbols.insert(symbol.to_string(), shape);
+
+        Ok(self)
+    }
+
+    /// Reads `symbol` in the guide's second column as the outcome to reach, for
+    /// [`Game::score_outcomes`].
+    pub fn outcome_symbol(mut self, symbol: &str, outcome: MatchOutcome) -> Self {
+        self.outcome_symbols.insert(symbol.to_string(), outcome);
+        self
+    }
+
+    /// Every shape, in cycle order.
+    pub fn shapes(&self) -> impl DoubleEndedIterator<Item = Shape> {
+        (0..self.shapes.len()).map(Shape)
+    }
+
+    /// Name of `shape`.
+    pub fn name(&self, shape: Shape) -> &str {
+        &self.shapes[shape.0].0
+    }
+
+    /// Points for playing `shape`.
+    pub fn shape_score(&self, shape: Shape) -> u64 {
+        self.shapes[shape.0].1
+    }
+
+    /// Outcome of us playing `me` against `opponent`.
+    pub fn outcome(&self, me: Shape, opponent: Shape) -> MatchOutcome {
+        let count = self.shapes.len();
+        match (me.0 + count - opponent.0) % count {
+            0 => MatchOutcome::Draw,
+            ahead if ahead <= count / 2 => MatchOutcome::Win,
+            _ => MatchOutcome::Loss,
         }
     }
-}
 
-impl Ord for Rps {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).expect("None never returned here")
+    /// Points for us playing `me` against `opponent`.
+    pub fn round_score(&self, me: Shape, opponent: Shape) -> u64 {
+        self.shape_score(me) + self.outcome_scores.score(self.outcome(me, opponent))
+    }
+
+    /// The shape to play against `opponent` to reach `outcome`. When several shapes do, the
+    /// highest scoring one, the first in cycle order on ties.
+    pub fn response_for(&self, opponent: Shape, outcome: MatchOutcome) -> Shape {
+        let count = self.shapes.len();
+        let offsets = match outcome {
+            MatchOutcome::Draw => 0..1,
+            MatchOutcome::Win => 1..count / 2 + 1,
+            MatchOutcome::Loss => count / 2 + 1..count,
+        };
+
+        // max_by_key keeps the last of equal keys, so go backwards to keep the first
+        offsets
+            .rev()
+            .map(|offset| Shape((opponent.0 + offset) % count))
+            .max_by_key(|shape| self.shape_score(*shape))
+            .expect("every outcome can be reached with 3 or more shapes")
+    }
+
+    /// Parses a strategy guide, one `opponent response` round per line. Responses can be
+    /// any response or outcome symbol, as which one applies depends on the part.
+    pub fn parse_guide(&self, input: &str) -> ParseResult<Vec<Round>> {
+        let mut guide = Vec::new();
+
+        for line in parse::lines(input) {
+            let [opponent, response] = line.tokens(" ")?;
+
+            let opponent = *self
+                .opponent_symbols
+                .get(opponent)
+                .ok_or_else(|| line.error(opponent, "Invalid opponent shape"))?;
+            if !self.response_symbols.contains_key(response)
+                && !self.outcome_symbols.contains_key(response)
+            {
+                return Err(line.error(
+                    response,
+                    format!("Invalid response, expected one of {}", self.response_list()),
+                ));
+            }
+
+            guide.push(Round {
+                opponent,
+                response: response.to_string(),
+            });
+        }
+
+        Ok(guide)
+    }
+
+    fn response_list(&self) -> String {
+        let mut symbols = self
+            .response_symbols
+            .keys()
+            .chain(self.outcome_symbols.keys())
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        symbols.sort_unstable();
+        symbols.dedup();
+
+        symbols.join(", ")
+    }
+
+    /// Shapes played reading each response as a shape.
+    pub fn shape_plan(&self, guide: &[Round]) -> Result<Vec<Shape>> {
+        guide
+            .iter()
+            .map(|round| {
+                self.response_symbols
+                    .get(&round.response)
+                    .copied()
+                    .ok_or_else(|| Error::msg(format!("Not a shape: {}", round.response)))
+            })
+            .collect()
+    }
+
+    /// Shapes played reading each response as the outcome to reach.
+    pub fn outcome_plan(&self, guide: &[Round]) -> Result<Vec<Shape>> {
+        guide
+            .iter()
+            .map(|round| {
+                let outcome = self
+                    .outcome_symbols
+                    .get(&round.response)
+                    .ok_or_else(|| Error::msg(format!("Not an outcome: {}", round.response)))?;
+
+                Ok(self.response_for(round.opponent, *outcome))
+            })
+            .collect()
+    }
+
+    /// Total score of playing `plan`, one shape per round of `guide`.
+    pub fn plan_total(&self, guide: &[Round], plan: &[Shape]) -> Result<u64> {
+        if plan.len() != guide.len() {
+            bail!("Plan has {} shapes for {} rounds", plan.len(), guide.len());
+        }
+
+        guide.iter().zip(plan).try_fold(0u64, |total, (round, me)| {
+            total
+                .checked_add(self.round_score(*me, round.opponent))
+                .ok_or_else(|| Error::msg("Total score doesn't fit in 64 bits"))
+        })
+    }
+
+    /// Total score reading each response as the shape to play.
+    pub fn score_shapes(&self, guide: &[Round]) -> Result<u64> {
+        self.plan_total(guide, &self.shape_plan(guide)?)
+    }
+
+    /// Total score reading each response as the outcome to reach.
+    pub fn score_outcomes(&self, guide: &[Round]) -> Result<u64> {
+        self.plan_total(guide, &self.outcome_plan(guide)?)
+    }
+
+    /// Highest scoring plan against `guide`'s opponents. Rounds are independent, so it plays
+    /// each round's highest scoring shape, the first in cycle order on ties.
+    pub fn best_plan(&self, guide: &[Round]) -> Result<Plan> {
+        // max_by_key keeps the last of equal keys, so go backwards to keep the first
+        self.plan(guide, |opponent| {
+            self.shapes()
+                .rev()
+                .max_by_key(|me| self.round_score(*me, opponent))
+        })
+    }
+
+    /// Lowest scoring plan against `guide`'s opponents, the first in cycle order on ties.
+    pub fn worst_plan(&self, guide: &[Round]) -> Result<Plan> {
+        self.plan(guide, |opponent| {
+            self.shapes()
+                .min_by_key(|me| self.round_score(*me, opponent))
+        })
+    }
+
+    fn plan(&self, guide: &[Round], pick: impl Fn(Shape) -> Option<Shape>) -> Result<Plan> {
+        let shapes = guide
+            .iter()
+            .map(|round| pick(round.opponent).expect("games have at least 3 shapes"))
+            .collect::<Vec<_>>();
+        let total = self.plan_total(guide, &shapes)?;
+
+        Ok(Plan { shapes, total })
+    }
+
+    /// Distribution of the total over plans picking each round's shape uniformly at random.
+    ///
+    /// Built exactly, one round at a time, by spreading every reachable total over the round's
+    /// scores. Fails if the totals span more than [`MAX_TOTALS`] values.
+    pub fn random_totals(&self, guide: &[Round]) -> Result<TotalDistribution> {
+        let share = 1.0 / self.shapes.len() as f64;
+
+        let mut min = 0u64;
+        let mut probabilities = vec![1.0];
+        for round in guide {
+            let scores = self
+                .shapes()
+                .map(|me| self.round_score(me, round.opponent))
+                .collect::<Vec<_>>();
+            let low = scores.iter().copied().min().unwrap_or(0);
+            let high = scores.iter().copied().max().unwrap_or(0);
+
+            let span = usize::try_from(high - low)
+                .ok()
+                .and_then(|span| span.checked_add(probabilities.len()))
+                .filter(|span| *span <= MAX_TOTALS);
+            let Some(span) = span else {
+                bail!("Totals span more than {} values", MAX_TOTALS);
+            };
+
+            let mut next = vec![0.0; span];
+            for score in scores {
+                let shift = (score - low) as usize;
+                for (idx, probability) in probabilities.iter().enumerate() {
+                    next[idx + shift] += probability * share;
+                }
+            }
+
+            // Round scores fit in 64 bits, but their sum doesn't have to
+            min = min
+                .checked_add(low)
+                .ok_or_else(|| Error::msg("Total score doesn't fit in 64 bits"))?;
+            probabilities = next;
+        }
+
+        Ok(TotalDistribution { min, probabilities })
+    }
+
+    /// Best and worst plans against `guide`, and how its two readings rank among random plans.
+    pub fn analyze(&self, guide: &[Round]) -> Result<GuideAnalysis> {
+        let random = self.random_totals(guide)?;
+        // A reading only applies when every response is a symbol of its kind
+        let reading = |plan: Result<Vec<Shape>>| -> Result<Option<Reading>> {
+            let Ok(plan) = plan else {
+                return Ok(None);
+            };
+            let total = self.plan_total(guide, &plan)?;
+
+            Ok(Some(Reading {
+                total,
+                percentile: random.percentile(total),
+            }))
+        };
+
+        Ok(GuideAnalysis {
+            best: self.best_plan(guide)?,
+            worst: self.worst_plan(guide)?,
+            shapes: reading(self.shape_plan(guide))?,
+            outcomes: reading(self.outcome_plan(guide))?,
+            random_mean: random.mean(),
+        })
     }
 }
 
-enum MatchOutcome {
-    Win,
-    Draw,
-    Loss,
+/// Most distinct totals [`Game::random_totals`] keeps track of.
+pub const MAX_TOTALS: usize = 10_000_000;
+
+/// A shape to play for every round of a guide, and the total it scores.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    /// Shape played in each round.
+    pub shapes: Vec<Shape>,
+    /// Total score.
+    pub total: u64,
 }
 
-impl MatchOutcome {
-    fn value(&self) -> u64 {
-        match self {
-            MatchOutcome::Win => 6,
-            MatchOutcome::Draw => 3,
-            MatchOutcome::Loss => 0,
-        }
+/// Probability of scoring each total with a uniformly random plan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TotalDistribution {
+    min: u64,
+    probabilities: Vec<f64>,
+}
+
+impl TotalDistribution {
+    /// Probability of scoring exactly `total`.
+    pub fn probability(&self, total: u64) -> f64 {
+        total
+            .checked_sub(self.min)
+            .and_then(|idx| self.probabilities.get(usize::try_from(idx).ok()?))
+            .copied()
+            .unwrap_or(0.0)
     }
+
+    /// Percentile rank of `total`: the percentage of random plans scoring less, counting
+    /// half of those scoring the same.
+    pub fn percentile(&self, total: u64) -> f64 {
+        let below = match total.checked_sub(self.min) {
+            Some(idx) => {
+                let idx = usize::try_from(idx).unwrap_or(usize::MAX);
+                self.probabilities[..idx.min(self.probabilities.len())]
+                    .iter()
+                    .sum()
+            }
+            None => 0.0,
+        };
+
+        100.0 * (below + self.probability(total) / 2.0)
+    }
+
+    /// Expected total.
+    pub fn mean(&self) -> f64 {
+        self.probabilities
+            .iter()
+            .enumerate()
+            .map(|(idx, probability)| (self.min as f64 + idx as f64) * probability)
+            .sum()
+    }
+}
+
+/// A guide's total under one reading of its responses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reading {
+    /// Total score.
+    pub total: u64,
+    /// Percentile rank of the total among random plans.
+    pub percentile: f64,
+}
+
+/// How a guide compares with every plan against the same opponents.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuideAnalysis {
+    /// Highest scoring plan.
+    pub best: Plan,
+    /// Lowest scoring plan.
+    pub worst: Plan,
+    /// Reading responses as shapes, unless some response isn't a shape.
+    pub shapes: Option<Reading>,
+    /// Reading responses as outcomes, unless some response isn't an outcome.
+    pub outcomes: Option<Reading>,
+    /// Expected total of a random plan.
+    pub random_mean: f64,
 }
 
-impl TryFrom<&str> for MatchOutcome {
-    type Error = Error;
+/// Text report of [`Game::analyze`], followed by the best and worst plans round by round
+/// when `plans` is set.
+pub fn report(game: &Game, guide: &[Round], plans: bool) -> Result<String> {
+    let analysis = game.analyze(guide)?;
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
-            "X" => Ok(MatchOutcome::Loss),
-            "Y" => Ok(MatchOutcome::Draw),
-            "Z" => Ok(MatchOutcome::Win),
-            _ => bail!("Invalid match outcome: {}", value),
+    let mut report = String::new();
+    let _ = writeln!(report, "rounds:      {}", guide.len());
+    let _ = writeln!(report, "best:        {}", analysis.best.total);
+    let _ = writeln!(report, "worst:       {}", analysis.worst.total);
+    let _ = writeln!(report, "random mean: {:.1}", analysis.random_mean);
+    for (label, reading) in [
+        ("as shapes:  ", analysis.shapes),
+        ("as outcomes:", analysis.outcomes),
+    ] {
+        let _ = match reading {
+            Some(reading) => writeln!(
+                report,
+                "{} {}, percentile {:.1}",
+                label, reading.total, reading.percentile
+            ),
+            None => writeln!(report, "{} doesn't apply", label),
+        };
+    }
+
+    if plans {
+        let _ = writeln!(
+            report,
+            "\n{:>5} {:<10} {:<10} worst",
+            "round", "opponent", "best"
+        );
+        let rounds = guide
+            .iter()
+            .zip(&analysis.best.shapes)
+            .zip(&analysis.worst.shapes);
+        for (idx, ((round, best), worst)) in rounds.enumerate() {
+            let _ = writeln!(
+                report,
+                "{:>5} {:<10} {:<10} {}",
+                idx + 1,
+                game.name(round.opponent),
+                game.name(*best),
+                game.name(*worst)
+            );
         }
     }
+
+    Ok(report)
 }
 
-pub fn day_2() -> Result<()> {
-    day_2_1()?;
-    day_2_2()
+/// A round of the strategy guide: the opponent's shape and the second column's symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Round {
+    /// Shape the opponent plays.
+    pub opponent: Shape,
+    /// Response symbol, read as a shape or an outcome depending on the part.
+    pub response: String,
 }
 
-fn day_2_1() -> Result<()> {
-    let file = File::open("input/day_2.txt")?;
-    let mut score = 0u64;
+/// [`Solution`] for day 2.
+pub struct Day2;
 
-    let lines = std::io::BufReader::new(file).lines();
-    for (line_num, line) in lines.enumerate() {
-        let line = line?;
-        let tokens = line.split(' ').collect::<Vec<_>>();
-        if tokens.len() != 2 {
-            bail!("Invalid number of choices on line {}", line_num + 1);
-        }
+impl Solution for Day2 {
+    const DAY: u32 = 2;
 
-        let opponent = Rps::try_from(tokens[0])?;
-        let me = Rps::try_from(tokens[1])?;
+    type Input = Vec<Round>;
 
-        score += me.value();
-        score += match me.cmp(&opponent) {
-            Ordering::Less => 0,
-            Ordering::Equal => 3,
-            Ordering::Greater => 6,
-        };
+    fn parse(input: &str) -> ParseResult<Self::Input> {
+        parse(input)
+    }
+
+    fn part1(guide: &Self::Input) -> Result<Answer> {
+        day_2_1(guide)
+    }
+
+    fn part2(guide: &Self::Input) -> Result<Answer> {
+        day_2_2(guide)
     }
+}
 
-    println!("Day 2-1: {}", score);
+/// Total score reading the second column as the shape to play.
+pub fn day_2_1(guide: &[Round]) -> Result<Answer> {
+    Ok(Game::rps().score_shapes(guide)?.into())
+}
 
-    Ok(())
+/// Total score reading the second column as the outcome to reach.
+pub fn day_2_2(guide: &[Round]) -> Result<Answer> {
+    Ok(Game::rps().score_outcomes(guide)?.into())
 }
 
-fn day_2_2() -> Result<()> {
-    let file = File::open("input/day_2.txt")?;
-    let mut score = 0u64;
+/// Parses a rock, paper, scissors strategy guide.
+pub fn parse(input: &str) -> ParseResult<Vec<Round>> {
+    Game::rps().parse_guide(input)
+}
 
-    let lines = std::io::BufReader::new(file).lines();
-    for (line_num, line) in lines.enumerate() {
-        let line = line?;
-        let tokens = line.split(' ').collect::<Vec<_>>();
-        if tokens.len() != 2 {
-            bail!("Invalid number of choices on line {}", line_num + 1);
-        }
+#[cfg(test)]
+mod test {
+    use super::*;
 
-        let opponent = Rps::try_from(tokens[0])?;
-        let outcome = MatchOutcome::try_from(tokens[1])?;
+    #[test]
+    fn test_rps() -> Result<()> {
+        let game = Game::rps();
+        let guide = game.parse_guide("A Y\nB X\nC Z\n")?;
 
-        score += outcome.value();
-        match outcome {
-            MatchOutcome::Win => score += opponent.winning_play().value(),
-            MatchOutcome::Draw => score += opponent.value(),
-            MatchOutcome::Loss => score += opponent.losing_play().value(),
+        assert_eq!(game.score_shapes(&guide)?, 15);
+        assert_eq!(game.score_outcomes(&guide)?, 12);
+
+        let error = game.parse_guide("A Y\nD X\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        let error = game.parse_guide("A W\n").unwrap_err();
+        assert_eq!(error.message, "Invalid response, expected one of X, Y, Z");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze() -> Result<()> {
+        let game = Game::rps();
+        let guide = game.parse_guide("A Y\nB X\nC Z\n")?;
+        let shape = |name| game.shape(name);
+
+        let analysis = game.analyze(&guide)?;
+        assert_eq!(
+            analysis.best.shapes,
+            vec![shape("Paper")?, shape("Scissors")?, shape("Rock")?]
+        );
+        assert_eq!(analysis.best.total, 24);
+        assert_eq!(
+            analysis.worst.shapes,
+            vec![shape("Scissors")?, shape("Rock")?, shape("Paper")?]
+        );
+        assert_eq!(analysis.worst.total, 6);
+        assert!((analysis.random_mean - 15.0).abs() < 1e-9);
+
+        // Of the 27 plans, 11 score under 15 and 5 exactly 15, and 8 score under 12 and 1 exactly
+        let (shapes, outcomes) = (analysis.shapes.unwrap(), analysis.outcomes.unwrap());
+        assert_eq!((shapes.total, outcomes.total), (15, 12));
+        assert!((shapes.percentile - 50.0).abs() < 1e-9);
+        assert!((outcomes.percentile - 100.0 * 8.5 / 27.0).abs() < 1e-9);
+
+        let random = game.random_totals(&guide)?;
+        assert!((random.probability(24) - 1.0 / 27.0).abs() < 1e-12);
+        assert_eq!((random.probability(5), random.probability(25)), (0.0, 0.0));
+        assert_eq!((random.percentile(0), random.percentile(100)), (0.0, 100.0));
+
+        assert!(game.plan_total(&guide, &analysis.best.shapes[..2]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rpsls() -> Result<()> {
+        let game = Game::rpsls();
+        let shape = |name| game.shape(name);
+
+        // Every rule of the game, as "winner beats loser"
+        let rules = [
+            ("Scissors", "Paper"),
+            ("Paper", "Rock"),
+            ("Rock", "Lizard"),
+            ("Lizard", "Spock"),
+            ("Spock", "Scissors"),
+            ("Scissors", "Lizard"),
+            ("Lizard", "Paper"),
+            ("Paper", "Spock"),
+            ("Spock", "Rock"),
+            ("Rock", "Scissors"),
+        ];
+        for (winner, loser) in rules {
+            assert_eq!(
+                game.outcome(shape(winner)?, shape(loser)?),
+                MatchOutcome::Win
+            );
+            assert_eq!(
+                game.outcome(shape(loser)?, shape(winner)?),
+                MatchOutcome::Loss
+            );
         }
+
+        // Both lizard and scissors beat paper, lizard scores more
+        assert_eq!(
+            game.response_for(shape("Paper")?, MatchOutcome::Win),
+            shape("Lizard")?
+        );
+        assert_eq!(
+            game.response_for(shape("Lizard")?, MatchOutcome::Loss),
+            shape("Spock")?
+        );
+
+        // Lizard loses to rock, spock to paper and scissors to spock. Reaching the outcomes
+        // instead plays rock, lizard and scissors
+        let guide = game.parse_guide("A Y\nB Z\nE X\n")?;
+        assert_eq!(game.score_shapes(&guide)?, 4 + 5 + 3);
+        assert_eq!(game.score_outcomes(&guide)?, (1 + 3) + (4 + 6) + 3);
+
+        // V isn't an outcome, so only the shapes reading applies
+        let guide = game.parse_guide("A V\nE Z\n")?;
+        let analysis = game.analyze(&guide)?;
+        assert_eq!(analysis.shapes.map(|reading| reading.total), Some(4 + 8));
+        assert_eq!(analysis.outcomes, None);
+        assert_eq!(analysis.best.total, (5 + 6) + (4 + 6));
+
+        Ok(())
     }
 
-    println!("Day 2-2: {}", score);
+    #[test]
+    fn test_custom_game() -> Result<()> {
+        let scores = OutcomeScores {
+            loss: 0,
+            draw: 1,
+            win: 2,
+        };
+        let game = Game::new(&[("Fire", 0), ("Water", 0), ("Air", 0)], scores)?
+            .opponent_symbol("f", "Fire")?
+            .response_symbol("w", "Water")?
+            .outcome_symbol("+", MatchOutcome::Win);
+        let guide = game.parse_guide("f w\nf +\n")?;
+
+        assert_eq!(game.score_shapes(&guide[..1])?, 2);
+        assert_eq!(game.score_outcomes(&guide[1..])?, 2);
+        assert!(game.score_outcomes(&guide).is_err());
 
-    Ok(())
+        assert!(Game::new(&[("Fire", 0), ("Water", 0)], scores).is_err());
+        assert!(Game::new(&[("Fire", 0), ("Fire", 0), ("Air", 0)], scores).is_err());
+        assert!(Game::new(&[("Fire", u64::MAX), ("Water", 0), ("Air", 0)], scores).is_err());
+        assert!(Game::rps().opponent_symbol("D", "Lizard").is_err());
+
+        Ok(())
+    }
 }
@@ -1,10 +1,16 @@
+//! Day 3: Rucksack Reorganization.
+
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::BufRead;
 
 use anyhow::{bail, Result};
 
-struct Rucksack {
+use crate::answer::Answer;
+use crate::parse::{self, Line, ParseError, ParseResult};
+use crate::solution::Solution;
+
+/// A rucksack split into its two compartments.
+#[derive(Debug, Clone)]
+pub struct Rucksack {
     compartment1: HashSet<char>,
     compartment2: HashSet<char>,
 }
@@ -14,7 +20,8 @@ impl Rucksack {
         compartment.chars().collect()
     }
 
-    fn item_value(item: char) -> Result<u32> {
+    /// Priority of an item: `a`-`z` are 1-26 and `A`-`Z` are 27-52.
+    pub fn item_value(item: char) -> Result<u32> {
         if item.is_ascii_uppercase() {
             Ok(item as u32 - 65 + 27)
         } else if item.is_ascii_lowercase() {
@@ -24,14 +31,16 @@ impl Rucksack {
         }
     }
 
-    fn common_items(&self) -> Vec<char> {
+    /// Items found in both compartments.
+    pub fn common_items(&self) -> Vec<char> {
         self.compartment1
             .intersection(&self.compartment2)
             .cloned()
             .collect()
     }
 
-    fn whole_bag(&self) -> HashSet<char> {
+    /// Every item in the rucksack.
+    pub fn whole_bag(&self) -> HashSet<char> {
         self.compartment1
             .union(&self.compartment2)
             .cloned()
@@ -39,34 +48,55 @@ impl Rucksack {
     }
 }
 
-impl From<String> for Rucksack {
-    fn from(contents: String) -> Self {
+impl TryFrom<Line<'_>> for Rucksack {
+    type Error = ParseError;
+
+    fn try_from(line: Line<'_>) -> Result<Self, Self::Error> {
+        let contents = line.text;
+        if let Some(idx) = contents.find(|item: char| !item.is_ascii_alphabetic()) {
+            return Err(line.error(&contents[idx..], "Invalid item"));
+        }
+
         let len = contents.len();
+        if !len.is_multiple_of(2) {
+            return Err(line.error(contents, "Odd number of items"));
+        }
 
         let comp1 = &contents[0..(len / 2)];
         let comp2 = &contents[(len / 2)..len];
 
-        Self {
+        Ok(Self {
             compartment1: Rucksack::build_compartment_set(comp1),
             compartment2: Rucksack::build_compartment_set(comp2),
-        }
+        })
     }
 }
 
-pub fn day_3() -> Result<()> {
-    day_3_1()?;
-    day_3_2()
-}
+/// [`Solution`] for day 3.
+pub struct Day3;
 
-fn day_3_1() -> Result<()> {
-    let file = File::open("input/day_3.txt")?;
+impl Solution for Day3 {
+    const DAY: u32 = 3;
 
-    let mut sum = 0u32;
-    let lines = std::io::BufReader::new(file).lines();
-    for line in lines {
-        let line = line?;
-        let rucksack = Rucksack::from(line);
+    type Input = Vec<Rucksack>;
+
+    fn parse(input: &str) -> ParseResult<Self::Input> {
+        parse(input)
+    }
 
+    fn part1(rucksacks: &Self::Input) -> Result<Answer> {
+        day_3_1(rucksacks)
+    }
+
+    fn part2(rucksacks: &Self::Input) -> Result<Answer> {
+        day_3_2(rucksacks)
+    }
+}
+
+/// Sum of the priorities of items in both compartments of each rucksack.
+pub fn day_3_1(rucksacks: &[Rucksack]) -> Result<Answer> {
+    let mut sum = 0u32;
+    for rucksack in rucksacks {
         let common_items = rucksack.common_items();
         sum += common_items
             .iter()
@@ -74,31 +104,19 @@ fn day_3_1() -> Result<()> {
             .sum::<u32>();
     }
 
-    println!("Day 3-1: {}", sum);
-
-    Ok(())
+    Ok(sum.into())
 }
 
-fn day_3_2() -> Result<()> {
-    let file = File::open("input/day_3.txt")?;
-
+/// Sum of the priorities of each group of three elves' badge.
+pub fn day_3_2(rucksacks: &[Rucksack]) -> Result<Answer> {
     let mut sum = 0u32;
-    let lines = std::io::BufReader::new(file)
-        .lines()
-        .map(|line| line.unwrap())
-        .collect::<Vec<_>>();
-    for chunk in lines.chunks(3) {
+    for chunk in rucksacks.chunks(3) {
         if chunk.len() != 3 {
             bail!("Input file lines not divisble by 3");
         }
 
-        let rucksacks = chunk
-            .iter()
-            .map(|items| Rucksack::from(items.clone()))
-            .collect::<Vec<_>>();
-
-        let mut intersect = rucksacks[0].whole_bag();
-        for rucksack in rucksacks[1..3].iter() {
+        let mut intersect = chunk[0].whole_bag();
+        for rucksack in chunk[1..3].iter() {
             intersect = intersect
                 .intersection(&rucksack.whole_bag())
                 .cloned()
@@ -116,7 +134,10 @@ fn day_3_2() -> Result<()> {
         sum += Rucksack::item_value(final_val[0])?;
     }
 
-    println!("Day 3-2: {}", sum);
+    Ok(sum.into())
+}
 
-    Ok(())
+/// Parses one rucksack per line.
+pub fn parse(input: &str) -> ParseResult<Vec<Rucksack>> {
+    parse::lines(input).map(Rucksack::try_from).collect()
 }
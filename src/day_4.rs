@@ -1,100 +1,112 @@
-use std::fs::File;
-use std::io::BufRead;
+//! Day 4: Camp Cleanup.
 
-use anyhow::{bail, Error, Result};
+use anyhow::Result;
 
-struct SectionRange {
-    start: u32,
-    end: u32,
+use crate::answer::Answer;
+use crate::parse::{self, Line, ParseError, ParseResult};
+use crate::solution::Solution;
+
+/// An inclusive range of section IDs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SectionRange {
+    /// First section.
+    pub start: u32,
+    /// Last section.
+    pub end: u32,
 }
 
 impl SectionRange {
-    fn contains(&self, other: &SectionRange) -> bool {
+    /// Whether `other` lies entirely within this range.
+    pub fn contains(&self, other: &SectionRange) -> bool {
         self.start <= other.start && self.end >= other.end
     }
 }
 
-impl TryFrom<&str> for SectionRange {
-    type Error = Error;
-
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let tokens = value.split('-').collect::<Vec<_>>();
-        if tokens.len() != 2 {
-            bail!("Invalid SectionRange string: {}", value);
-        }
+impl SectionRange {
+    fn parse(line: &Line, value: &str) -> ParseResult<Self> {
+        let (start, end) = value
+            .split_once('-')
+            .ok_or_else(|| line.error(value, "Expected a section range like 2-4"))?;
 
-        let start = tokens[0].parse::<u32>()?;
-        let end = tokens[1].parse::<u32>()?;
+        let start = line.parse::<u32>(start, "section")?;
+        let end = line.parse::<u32>(end, "section")?;
 
         Ok(Self { start, end })
     }
 }
 
-struct ElfPair {
-    elf1: SectionRange,
-    elf2: SectionRange,
+/// The section assignments of a pair of elves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfPair {
+    /// First elf's sections.
+    pub elf1: SectionRange,
+    /// Second elf's sections.
+    pub elf2: SectionRange,
 }
 
-impl TryFrom<String> for ElfPair {
-    type Error = Error;
+impl TryFrom<Line<'_>> for ElfPair {
+    type Error = ParseError;
 
-    fn try_from(value: String) -> Result<Self, Self::Error> {
-        let tokens = value.split(',').collect::<Vec<_>>();
-        if tokens.len() != 2 {
-            bail!("Invalid SectionRange string: {}", value);
-        }
+    fn try_from(line: Line<'_>) -> Result<Self, Self::Error> {
+        let [elf1, elf2] = line.tokens(",")?;
 
-        let elf1 = SectionRange::try_from(tokens[0])?;
-        let elf2 = SectionRange::try_from(tokens[1])?;
+        let elf1 = SectionRange::parse(&line, elf1)?;
+        let elf2 = SectionRange::parse(&line, elf2)?;
 
         Ok(Self { elf1, elf2 })
     }
 }
 
-pub fn day_4() -> Result<()> {
-    day_4_1()?;
-    day_4_2()
+/// [`Solution`] for day 4.
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u32 = 4;
+
+    type Input = Vec<ElfPair>;
+
+    fn parse(input: &str) -> ParseResult<Self::Input> {
+        parse(input)
+    }
+
+    fn part1(pairs: &Self::Input) -> Result<Answer> {
+        day_4_1(pairs)
+    }
+
+    fn part2(pairs: &Self::Input) -> Result<Answer> {
+        day_4_2(pairs)
+    }
 }
 
-fn day_4_1() -> Result<()> {
+/// Number of pairs where one assignment fully contains the other.
+pub fn day_4_1(pairs: &[ElfPair]) -> Result<Answer> {
     fn full_overlap(pair: &ElfPair) -> bool {
         pair.elf1.contains(&pair.elf2) || pair.elf2.contains(&pair.elf1)
     }
 
-    let sum = day_4_inner(full_overlap)?;
+    let sum = day_4_inner(pairs, full_overlap);
 
-    println!("Day 4-1: {}", sum);
-
-    Ok(())
+    Ok(sum.into())
 }
 
-fn day_4_2() -> Result<()> {
+/// Number of pairs whose assignments overlap at all.
+pub fn day_4_2(pairs: &[ElfPair]) -> Result<Answer> {
     fn partial_overlap(pair: &ElfPair) -> bool {
         pair.elf1.start <= pair.elf2.end && pair.elf2.start <= pair.elf1.end
     }
 
-    let sum = day_4_inner(partial_overlap)?;
-
-    println!("Day 4-2: {}", sum);
+    let sum = day_4_inner(pairs, partial_overlap);
 
-    Ok(())
+    Ok(sum.into())
 }
 
 type CompFunction = fn(&ElfPair) -> bool;
 
-fn day_4_inner(comp_fn: CompFunction) -> Result<u32> {
-    let file = File::open("input/day_4.txt")?;
-
-    let mut sum = 0u32;
-    let lines = std::io::BufReader::new(file).lines();
-    for line in lines {
-        let line = line?;
-
-        let elf_pair = ElfPair::try_from(line)?;
-        if comp_fn(&elf_pair) {
-            sum += 1;
-        }
-    }
+fn day_4_inner(pairs: &[ElfPair], comp_fn: CompFunction) -> u32 {
+    pairs.iter().filter(|pair| comp_fn(pair)).count() as u32
+}
 
-    Ok(sum)
+/// Parses one `a-b,c-d` pair per line.
+pub fn parse(input: &str) -> ParseResult<Vec<ElfPair>> {
+    parse::lines(input).map(ElfPair::try_from).collect()
 }
@@ -1,11 +1,14 @@
-use std::io::{BufRead, Lines};
-use std::{fs::File, io::BufReader};
+//! Day 5: Supply Stacks.
 
 use anyhow::{Error, Result};
-use itertools::Itertools;
 
-#[derive(Debug, Default)]
-struct CrateYard {
+use crate::answer::Answer;
+use crate::parse::{self, Line, ParseError, ParseResult};
+use crate::solution::Solution;
+
+/// Stacks of crates, bottom first.
+#[derive(Debug, Default, Clone)]
+pub struct CrateYard {
     piles: Vec<Vec<char>>,
 }
 
@@ -35,106 +38,189 @@ impl CrateYard {
         let src = src - 1;
         let dest = dest - 1;
 
-        let split = self.piles[src].len() - count;
+        let split = self.piles[src]
+            .len()
+            .checked_sub(count)
+            .ok_or_else(|| Error::msg(format!("More crates popped than in pile: {}", count)))?;
 
         let crates = self.piles[src].split_off(split);
         self.piles[dest].extend(crates);
 
         Ok(())
     }
+
+    /// The crate on top of each stack, in stack order.
+    pub fn top_crates(&self) -> String {
+        let mut top_str = String::new();
+        for pile in &self.piles {
+            if let Some(crate_char) = pile.last() {
+                top_str.push(*crate_char);
+            }
+        }
+
+        top_str
+    }
 }
 
-fn build_crate_yard(lines: &mut Lines<BufReader<File>>) -> Result<CrateYard> {
-    let mut crate_yard = CrateYard::default();
+/// A `move N from A to B` step. Stacks are numbered from 1.
+#[derive(Debug, Clone, Copy)]
+pub struct Move {
+    /// Number of crates moved.
+    pub count: usize,
+    /// Stack the crates come from.
+    pub src: usize,
+    /// Stack the crates go to.
+    pub dest: usize,
+}
 
-    let mut yard_lines = Vec::new();
-    let mut next_line = lines.next().unwrap()?;
-    while !next_line.chars().nth(1).unwrap().is_numeric() {
-        yard_lines.push(next_line);
-        next_line = lines.next().unwrap()?;
+impl Move {
+    fn parse(line: Line, stack_count: usize) -> ParseResult<Self> {
+        let [_, count, _, src, _, dest] = line.tokens(" ")?;
+        let count = line.parse::<usize>(count, "crate count")?;
+
+        let stack = |token| {
+            let stack = line.parse::<usize>(token, "stack")?;
+            if stack == 0 || stack > stack_count {
+                return Err(line.error(
+                    token,
+                    format!("No such stack, expected 1 to {}", stack_count),
+                ));
+            }
+
+            Ok(stack)
+        };
+        let src = stack(src)?;
+        let dest = stack(dest)?;
+
+        Ok(Self { count, src, dest })
     }
+}
+
+fn build_crate_yard<'a>(lines: &mut impl Iterator<Item = Line<'a>>) -> ParseResult<CrateYard> {
+    let mut crate_yard = CrateYard::default();
 
-    for line in yard_lines.iter_mut().rev() {
-        let mut chunk_index = 0;
-        for chars in &line.chars().chunks(4) {
-            let char_string = chars.collect::<Vec<_>>();
-            let crate_char = char_string
-                .get(1)
-                .ok_or_else(|| Error::msg("Invalid crate line"))?;
-
-            if !crate_char.is_alphabetic() {
-                chunk_index += 1;
-                continue;
-            } else {
-                crate_yard.push(*crate_char, chunk_index);
-                chunk_index += 1;
+    let mut yard_lines = Vec::new();
+    let label_line = loop {
+        let line = lines
+            .next()
+            .ok_or_else(|| ParseError::end_of_input(yard_lines.len(), "Missing stack numbers"))?;
+
+        match line.text.chars().nth(1) {
+            Some(label) if label.is_numeric() => break line,
+            Some(_) => yard_lines.push(line),
+            None => return Err(line.error(line.text, "Expected crates or stack numbers")),
+        }
+    };
+
+    for line in yard_lines.iter().rev() {
+        let slots = line.text.char_indices().skip(1).step_by(4);
+        for (chunk_index, (idx, crate_char)) in slots.enumerate() {
+            if crate_char.is_alphabetic() {
+                crate_yard.push(crate_char, chunk_index);
+            } else if crate_char != ' ' {
+                let token = &line.text[idx..idx + crate_char.len_utf8()];
+                return Err(line.error(token, "Invalid crate"));
             }
         }
     }
 
-    // Kind of silly, but I'm tired
-    lines.next();
+    let stack_count = label_line.text.split_whitespace().count();
+    if crate_yard.piles.len() > stack_count {
+        return Err(label_line.error(label_line.text, "Crates stacked past the last stack number"));
+    }
+    crate_yard.piles.resize(stack_count, Vec::new());
+
+    if let Some(line) = lines.next().filter(|line| !line.text.is_empty()) {
+        return Err(line.error(line.text, "Expected a blank line before the moves"));
+    }
 
     Ok(crate_yard)
 }
 
-pub fn day_5() -> Result<()> {
-    day_5_1()?;
-    day_5_2()
-}
+/// [`Solution`] for day 5.
+pub struct Day5;
 
-fn day_5_1() -> Result<()> {
-    let file = File::open("input/day_5.txt")?;
-    let mut lines = std::io::BufReader::new(file).lines();
+impl Solution for Day5 {
+    const DAY: u32 = 5;
 
-    let mut crate_yard = build_crate_yard(&mut lines)?;
+    type Input = (CrateYard, Vec<Move>);
 
-    for line in lines {
-        let line = line?;
-        let tokens = line.split(' ').collect::<Vec<_>>();
-        let count = tokens[1].parse::<usize>()?;
-        let src = tokens[3].parse::<usize>()?;
-        let dest = tokens[5].parse::<usize>()?;
+    fn parse(input: &str) -> ParseResult<Self::Input> {
+        parse(input)
+    }
 
-        crate_yard.move_crate(count, src, dest)?;
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        day_5_1(input)
     }
 
-    let mut top_str = String::new();
-    for pile in crate_yard.piles {
-        if let Some(crate_char) = pile.last() {
-            top_str.push(*crate_char);
-        }
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        day_5_2(input)
     }
+}
+
+/// Top crates after moving crates one at a time.
+pub fn day_5_1((crate_yard, moves): &(CrateYard, Vec<Move>)) -> Result<Answer> {
+    let mut crate_yard = crate_yard.clone();
 
-    println!("Day 5-1: {}", top_str);
+    for crate_move in moves {
+        crate_yard.move_crate(crate_move.count, crate_move.src, crate_move.dest)?;
+    }
 
-    Ok(())
+    Ok(crate_yard.top_crates().into())
 }
 
-fn day_5_2() -> Result<()> {
-    let file = File::open("input/day_5.txt")?;
-    let mut lines = std::io::BufReader::new(file).lines();
+/// Top crates after moving each step's crates all at once.
+pub fn day_5_2((crate_yard, moves): &(CrateYard, Vec<Move>)) -> Result<Answer> {
+    let mut crate_yard = crate_yard.clone();
 
-    let mut crate_yard = build_crate_yard(&mut lines)?;
+    for crate_move in moves {
+        crate_yard.move_crate_n(crate_move.count, crate_move.src, crate_move.dest)?;
+    }
 
-    for line in lines {
-        let line = line?;
-        let tokens = line.split(' ').collect::<Vec<_>>();
-        let count = tokens[1].parse::<usize>()?;
-        let src = tokens[3].parse::<usize>()?;
-        let dest = tokens[5].parse::<usize>()?;
+    Ok(crate_yard.top_crates().into())
+}
 
-        crate_yard.move_crate_n(count, src, dest)?;
-    }
+/// Parses the starting stacks drawing followed by the moves.
+pub fn parse(input: &str) -> ParseResult<(CrateYard, Vec<Move>)> {
+    let mut lines = parse::lines(input);
 
-    let mut top_str = String::new();
-    for pile in crate_yard.piles {
-        if let Some(crate_char) = pile.last() {
-            top_str.push(*crate_char);
-        }
+    let crate_yard = build_crate_yard(&mut lines)?;
+    let stack_count = crate_yard.piles.len();
+    let moves = lines
+        .map(|line| Move::parse(line, stack_count))
+        .collect::<ParseResult<Vec<_>>>()?;
+
+    Ok((crate_yard, moves))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const YARD: &str = "    [D]\n[N] [C]\n 1   2\n\n";
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let (crate_yard, moves) = parse(&format!("{}move 2 from 2 to 1\n", YARD))?;
+
+        assert_eq!(crate_yard.top_crates(), "ND");
+        assert_eq!((moves[0].count, moves[0].src, moves[0].dest), (2, 2, 1));
+
+        Ok(())
     }
 
-    println!("Day 5-2: {}", top_str);
+    #[test]
+    fn test_parse_errors() {
+        let error = parse(&format!("{}move 1 from 3 to 1\n", YARD)).unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (5, 13, "3")
+        );
 
-    Ok(())
+        let error = parse(&format!("{}move x from 1 to 2\n", YARD)).unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (5, 6, "x"));
+
+        let error = parse("    [D]\n[N] [C]\n").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 1));
+    }
 }
@@ -1,73 +1,99 @@
-use std::{collections::HashMap, fs};
+//! Day 6: Tuning Trouble.
+
+use std::collections::HashMap;
 
 use anyhow::{bail, Result};
 
-pub fn day_6() -> Result<()> {
-    day_6_1()?;
-    day_6_2()
-}
+use crate::answer::Answer;
+use crate::parse::{self, ParseError, ParseResult};
+use crate::solution::Solution;
 
-// This code sucks and so do I
-fn find_unique_seq(count: usize) -> Result<usize> {
-    let code = fs::read_to_string("input/day_6.txt")?;
+/// [`Solution`] for day 6.
+pub struct Day6;
 
-    let mut char_count = count;
-    let mut char_map = HashMap::new();
-    let mut window_start = 0;
-    let mut window_end = count - 1;
-    for ch in code[0..count].chars() {
-        let entry = char_map.entry(ch).or_insert(0usize);
-        *entry += 1;
+impl Solution for Day6 {
+    const DAY: u32 = 6;
+
+    type Input = String;
+
+    fn parse(input: &str) -> ParseResult<Self::Input> {
+        parse(input)
     }
 
-    if char_map.len() == count {
-        return Ok(count);
+    fn part1(code: &Self::Input) -> Result<Answer> {
+        day_6_1(code)
     }
 
-    for _ in count..code.len() {
-        window_end += 1;
-        {
-            let new_char = code.chars().nth(window_end).unwrap();
-            let entry = char_map.entry(new_char).or_insert(0usize);
-            *entry += 1;
-        }
+    fn part2(code: &Self::Input) -> Result<Answer> {
+        day_6_2(code)
+    }
+}
 
-        let old_char = code.chars().nth(window_start).unwrap();
-        {
-            let entry = char_map.entry(old_char).or_insert(0usize);
-            *entry -= 1;
-        }
-        if *char_map.get(&old_char).unwrap() == 0 {
-            char_map.remove(&old_char).unwrap();
-        }
-        window_start += 1;
+/// Number of characters read up to and including the first run of `count` distinct characters.
+pub fn find_unique_seq(code: &str, count: usize) -> Result<usize> {
+    if count == 0 {
+        bail!("Markers need at least one character");
+    }
+    // Indexed by character rather than byte, so any text works
+    let chars = code.chars().collect::<Vec<_>>();
+    if chars.len() < count {
+        bail!("Datastream shorter than {} characters", count);
+    }
 
-        char_count += 1;
+    // This code sucks and so do I
+    let mut char_map = HashMap::new();
+    for &ch in &chars[..count] {
+        *char_map.entry(ch).or_insert(0usize) += 1;
+    }
 
-        if char_map.len() == count {
-            break;
+    let mut char_count = count;
+    while char_map.len() != count {
+        let Some(&new_char) = chars.get(char_count) else {
+            bail!("Did not find start sequence")
+        };
+        *char_map.entry(new_char).or_insert(0usize) += 1;
+
+        let old_char = chars[char_count - count];
+        if let Some(entry) = char_map.get_mut(&old_char) {
+            *entry -= 1;
+            if *entry == 0 {
+                char_map.remove(&old_char);
+            }
         }
-    }
 
-    if char_count == code.len() {
-        bail!("Did not find start sequence")
+        char_count += 1;
     }
 
     Ok(char_count)
 }
 
-fn day_6_1() -> Result<()> {
-    let char_count = find_unique_seq(4)?;
+/// End of the first start-of-packet marker, 4 distinct characters.
+pub fn day_6_1(code: &str) -> Result<Answer> {
+    let char_count = find_unique_seq(code, 4)?;
+
+    Ok(char_count.into())
+}
 
-    println!("Day 6-1: {}", char_count);
+/// End of the first start-of-message marker, 14 distinct characters.
+pub fn day_6_2(code: &str) -> Result<Answer> {
+    let char_count = find_unique_seq(code, 14)?;
 
-    Ok(())
+    Ok(char_count.into())
 }
 
-fn day_6_2() -> Result<()> {
-    let char_count = find_unique_seq(14)?;
+/// Parses the datastream, a single line of lowercase letters.
+pub fn parse(input: &str) -> ParseResult<String> {
+    let mut lines = parse::lines(input);
+    let line = lines
+        .next()
+        .ok_or_else(|| ParseError::end_of_input(0, "Missing datastream"))?;
 
-    println!("Day 6-2: {}", char_count);
+    if let Some(idx) = line.text.find(|ch: char| !ch.is_ascii_lowercase()) {
+        return Err(line.error(&line.text[idx..], "Invalid datastream character"));
+    }
+    if let Some(extra) = lines.find(|line| !line.text.is_empty()) {
+        return Err(extra.error(extra.text, "Expected a single datastream"));
+    }
 
-    Ok(())
+    Ok(line.text.to_string())
 }
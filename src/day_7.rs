@@ -1,11 +1,16 @@
-use std::fs::File;
-use std::io::BufRead;
+//! Day 7: No Space Left On Device.
+
 use std::{collections::BTreeSet, convert::TryFrom};
 
 use anyhow::{bail, Error, Result};
 use indextree::{Arena, NodeId};
 
-struct ElfSystem {
+use crate::answer::Answer;
+use crate::parse::{self, Line, ParseError, ParseResult};
+use crate::solution::Solution;
+
+/// Directory tree rebuilt from a terminal session. Each node holds the size of the files directly in it.
+pub struct ElfSystem {
     tree: Arena<usize>,
     size: usize,
     root: NodeId,
@@ -26,33 +31,43 @@ enum Cmd {
     Ls,
 }
 
-impl TryFrom<String> for Cmd {
-    type Error = Error;
+impl TryFrom<Line<'_>> for Cmd {
+    type Error = ParseError;
 
-    fn try_from(cmd_string: String) -> Result<Self, Self::Error> {
-        let tokens = cmd_string.split(' ').collect::<Vec<_>>();
+    fn try_from(line: Line<'_>) -> Result<Self, Self::Error> {
+        let cmd = line.strip_prefix("$ ")?;
 
-        let cmd = match tokens[0] {
-            "cd" => Cmd::Cd(tokens[1].into()),
-            "ls" => Cmd::Ls,
-            _ => {
-                bail!("Invalid command")
-            }
-        };
-
-        Ok(cmd)
+        match cmd.split_once(' ') {
+            Some(("cd", path)) => Ok(Cmd::Cd(path.into())),
+            None if cmd == "ls" => Ok(Cmd::Ls),
+            _ => Err(line.error(cmd, "Invalid command")),
+        }
     }
 }
 
-pub fn day_7() -> Result<()> {
-    day_7_1()?;
-    day_7_2()
-}
+/// [`Solution`] for day 7.
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u32 = 7;
+
+    type Input = ElfSystem;
+
+    fn parse(input: &str) -> ParseResult<Self::Input> {
+        build_fs_tree(input)
+    }
+
+    fn part1(es: &Self::Input) -> Result<Answer> {
+        day_7_1(es)
+    }
 
-// 2061777
-fn day_7_1() -> Result<()> {
-    let es = build_fs_tree()?;
+    fn part2(es: &Self::Input) -> Result<Answer> {
+        day_7_2(es)
+    }
+}
 
+/// Sum of the sizes of directories holding at most 100000.
+pub fn day_7_1(es: &ElfSystem) -> Result<Answer> {
     let mut size_stack = Vec::new();
     let mut cur_size = 0usize;
     let mut total_size = 0usize;
@@ -74,18 +89,24 @@ fn day_7_1() -> Result<()> {
         }
     }
 
-    println!("Day 7-1: {}", total_size);
-
-    Ok(())
+    Ok(total_size.into())
 }
 
-fn day_7_2() -> Result<()> {
+/// Size of the smallest directory that frees enough space for the update.
+pub fn day_7_2(es: &ElfSystem) -> Result<Answer> {
     const FS_SIZE: usize = 70_000_000;
     const MIN_SIZE: usize = 30_000_000;
 
-    let es = build_fs_tree()?;
-
-    let size_needed = MIN_SIZE - (FS_SIZE - es.size);
+    let Some(free) = FS_SIZE.checked_sub(es.size) else {
+        bail!(
+            "Files take up {}, more than the disk's {}",
+            es.size,
+            FS_SIZE
+        );
+    };
+    let Some(size_needed) = MIN_SIZE.checked_sub(free) else {
+        bail!("{} is already free, nothing needs deleting", free);
+    };
     let mut size_stack = Vec::new();
     let mut cur_size = 0usize;
     let mut size_set = BTreeSet::new();
@@ -107,33 +128,38 @@ fn day_7_2() -> Result<()> {
         }
     }
 
-    let smallest_dir = size_set.iter().next().unwrap();
-    println!("Day 7-1: {}", smallest_dir);
+    let smallest_dir = size_set
+        .first()
+        .ok_or_else(|| Error::msg("No directory frees enough space"))?;
 
-    Ok(())
+    Ok((*smallest_dir).into())
 }
 
-fn build_fs_tree() -> Result<ElfSystem> {
-    let file = File::open("input/day_7.txt")?;
-
+/// Replays `cd` and `ls` output into an [`ElfSystem`].
+pub fn build_fs_tree(input: &str) -> ParseResult<ElfSystem> {
     let mut es = ElfSystem::default();
-    let mut lines = std::io::BufReader::new(file).lines();
+    let mut lines = parse::lines(input);
 
-    // Skip the first line and create root node
-    let _ = lines.next().unwrap();
+    // Skip the first line, the root node already exists
+    match lines.next() {
+        Some(line) if line.text == "$ cd /" => {}
+        Some(line) => return Err(line.error(line.text, "Expected \"$ cd /\"")),
+        None => return Err(ParseError::end_of_input(0, "Empty terminal output")),
+    }
     let mut cur_dir = vec!["/".to_string()];
 
     let mut cur_node = es.root;
     for line in lines {
-        let line = line?;
         let node = es.tree.get_mut(cur_node).unwrap();
 
-        if line.starts_with('$') {
-            let cmd = Cmd::try_from(line[2..].to_string())?;
+        if line.text.starts_with('$') {
+            let cmd = Cmd::try_from(line)?;
             match cmd {
                 Cmd::Cd(path) => {
                     if path.as_str() == ".." {
-                        cur_node = node.parent().unwrap();
+                        cur_node = node.parent().ok_or_else(|| {
+                            line.error(line.text, "Can't leave the root directory")
+                        })?;
                         cur_dir.pop();
                     } else {
                         cur_dir.push(path);
@@ -148,12 +174,17 @@ fn build_fs_tree() -> Result<ElfSystem> {
             continue;
         }
 
-        let tokens = line.split(' ').collect::<Vec<_>>();
-        if let Ok(size) = tokens[0].parse::<usize>() {
+        let [size, _name] = line.tokens(" ")?;
+        if size != "dir" {
+            let file_size = line.parse::<usize>(size, "file size")?;
             let node_size = node.get_mut();
 
-            es.size += size;
-            *node_size += size;
+            // No directory holds more than the total, so checking it covers them all
+            es.size = es
+                .size
+                .checked_add(file_size)
+                .ok_or_else(|| line.error(size, "Total file size too large"))?;
+            *node_size += file_size;
         }
     }
 
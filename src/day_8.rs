@@ -1,26 +1,42 @@
-use itertools::Itertools;
+//! Day 8: Treetop Tree House.
 
 use anyhow::Result;
 
-pub fn day_8() -> Result<()> {
-    day_8_1();
-    day_8_2();
+use crate::answer::Answer;
+use crate::grid::Grid;
+use crate::parse::ParseResult;
+use crate::solution::Solution;
 
-    Ok(())
-}
+/// [`Solution`] for day 8.
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u32 = 8;
+
+    type Input = Grid<u32>;
+
+    fn parse(input: &str) -> ParseResult<Self::Input> {
+        parse(input)
+    }
 
-fn parse() -> Vec<Vec<u32>> {
-    let input = std::fs::read_to_string("input/day_8.txt").unwrap();
+    fn part1(trees: &Self::Input) -> Result<Answer> {
+        day_8_1(trees)
+    }
 
-    input
-        .lines()
-        .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()).collect())
-        .collect()
+    fn part2(trees: &Self::Input) -> Result<Answer> {
+        day_8_2(trees)
+    }
 }
 
-fn directions(grid: &[Vec<u32>], x: usize, y: usize) -> [Vec<u32>; 4] {
-    let row = grid[y].clone();
-    let column = grid.iter().map(|row| row[x]).collect::<Vec<u32>>();
+/// Parses rows of tree heights.
+pub fn parse(input: &str) -> ParseResult<Grid<u32>> {
+    Grid::parse(input, "tree height", |c| c.to_digit(10))
+}
+
+/// Heights seen looking up, down, left and right from the tree at `(x, y)`, nearest first.
+fn sight_lines(trees: &Grid<u32>, x: usize, y: usize) -> [Vec<u32>; 4] {
+    let row = trees.row(y);
+    let column = trees.column(x).copied().collect::<Vec<_>>();
 
     let (up, down) = column.split_at(y);
     let (left, right) = row.split_at(x);
@@ -33,35 +49,27 @@ fn directions(grid: &[Vec<u32>], x: usize, y: usize) -> [Vec<u32>; 4] {
     [up, down, left, right]
 }
 
-pub fn day_8_1() {
-    let trees = parse();
-    let len = trees.len();
-
-    let visible_trees = (1..len - 1)
-        .cartesian_product(1..len - 1)
-        .map(|(y, x)| {
-            let height = trees[y][x];
-            directions(&trees, x, y)
+/// Number of trees visible from outside the grid.
+pub fn day_8_1(trees: &Grid<u32>) -> Result<Answer> {
+    // Edge trees have an empty sight line, so they count as visible without special casing
+    let visible_trees = trees
+        .iter()
+        .filter(|&((x, y), &height)| {
+            sight_lines(trees, x, y)
                 .iter()
-                .map(|direction| direction.iter().all(|h| *h < height))
-                .any(|visible| visible)
+                .any(|direction| direction.iter().all(|h| *h < height))
         })
-        .filter(|visible| *visible)
-        .count()
-        + (len - 1) * 4;
+        .count();
 
-    println!("Day 8-1: {}", visible_trees);
+    Ok(visible_trees.into())
 }
 
-pub fn day_8_2() {
-    let trees = parse();
-    let len = trees.len();
-
-    let scenic_score = (1..len - 1)
-        .cartesian_product(1..len - 1)
-        .map(|(y, x)| {
-            let height = trees[y][x];
-            directions(&trees, x, y)
+/// Highest scenic score of any tree.
+pub fn day_8_2(trees: &Grid<u32>) -> Result<Answer> {
+    let scenic_score = trees
+        .iter()
+        .map(|((x, y), &height)| {
+            sight_lines(trees, x, y)
                 .iter()
                 .map(|direction| {
                     direction
@@ -73,7 +81,7 @@ pub fn day_8_2() {
                 .product::<usize>()
         })
         .max()
-        .unwrap();
+        .unwrap_or(0);
 
-    println!("Day 8-2: {}", scenic_score);
+    Ok(scenic_score.into())
 }
@@ -1,103 +1,103 @@
+//! Day 9: Rope Bridge.
+
 use std::collections::HashSet;
 
-use anyhow::{bail, Error, Result};
+use anyhow::Result;
+
+use crate::answer::Answer;
+use crate::geometry::{Coords, Point2};
+use crate::parse::{self, Line, ParseError, ParseResult};
+use crate::solution::Solution;
 
-#[derive(Clone, Copy)]
-enum Cmd {
+/// A head move: direction and number of steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cmd {
+    /// Move up.
     Up(i32),
+    /// Move right.
     Right(i32),
+    /// Move left.
     Left(i32),
+    /// Move down.
     Down(i32),
 }
 
-impl TryFrom<&str> for Cmd {
-    type Error = Error;
-
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let tokens = value.split(' ').collect::<Vec<_>>();
+impl TryFrom<Line<'_>> for Cmd {
+    type Error = ParseError;
 
-        if tokens.len() != 2 {
-            bail!("Invalid command output");
-        }
+    fn try_from(line: Line<'_>) -> Result<Self, Self::Error> {
+        let [direction, move_count] = line.tokens(" ")?;
 
-        let move_count = tokens[1].parse::<i32>()?;
-        match tokens[0] {
+        let move_count = line.parse::<i32>(move_count, "move count")?;
+        match direction {
             "U" => Ok(Cmd::Up(move_count)),
             "R" => Ok(Cmd::Right(move_count)),
             "L" => Ok(Cmd::Left(move_count)),
             "D" => Ok(Cmd::Down(move_count)),
-            _ => bail!("Invalid move type"),
+            _ => Err(line.error(direction, "Invalid move type")),
         }
     }
 }
 
-pub fn day_9() -> Result<()> {
-    day_9_1()?;
-    day_9_2()
-}
+/// [`Solution`] for day 9.
+pub struct Day9;
 
-fn day_9_1() -> Result<()> {
-    let cmds = parse()?;
+impl Solution for Day9 {
+    const DAY: u32 = 9;
 
-    let mut tail_map = vec![(0, 0)].into_iter().collect::<HashSet<_>>();
-    let mut rope = [(0, 0); 2].to_vec();
+    type Input = Vec<Cmd>;
 
-    cmds.iter()
-        .for_each(|cmd| iterate_moves(*cmd, &mut rope, &mut tail_map));
+    fn parse(input: &str) -> ParseResult<Self::Input> {
+        parse(input)
+    }
 
-    println!("Day 9-1: {}", tail_map.len());
+    fn part1(cmds: &Self::Input) -> Result<Answer> {
+        day_9_1(cmds)
+    }
 
-    Ok(())
+    fn part2(cmds: &Self::Input) -> Result<Answer> {
+        day_9_2(cmds)
+    }
 }
 
-fn day_9_2() -> Result<()> {
-    let cmds = parse()?;
-
-    let mut tail_map = vec![(0, 0)].into_iter().collect::<HashSet<_>>();
-    let mut rope = [(0, 0); 10].to_vec();
-
-    cmds.iter()
-        .for_each(|cmd| iterate_moves(*cmd, &mut rope, &mut tail_map));
-
-    println!("Day 9-2: {}", tail_map.len());
-
-    Ok(())
+/// Positions visited by the tail of a 2 knot rope.
+pub fn day_9_1(cmds: &[Cmd]) -> Result<Answer> {
+    Ok(tail_positions(cmds, 2).into())
 }
 
-fn cmd_to_move_step(cmd: Cmd) -> (i32, i32) {
-    match cmd {
-        Cmd::Up(_) => (0, 1),
-        Cmd::Right(_) => (1, 0),
-        Cmd::Left(_) => (-1, 0),
-        Cmd::Down(_) => (0, -1),
-    }
+/// Positions visited by the tail of a 10 knot rope.
+pub fn day_9_2(cmds: &[Cmd]) -> Result<Answer> {
+    Ok(tail_positions(cmds, 10).into())
 }
 
-fn calc_tail_pos(head: (i32, i32), tail: (i32, i32)) -> (i32, i32) {
-    let x_diff = head.0 - tail.0;
-    let y_diff = head.1 - tail.1;
+fn tail_positions(cmds: &[Cmd], knots: usize) -> usize {
+    let mut tail_map = HashSet::from([Point2::ORIGIN]);
+    let mut rope = vec![Point2::ORIGIN; knots];
 
-    (tail.0 + x_diff.signum(), tail.1 + y_diff.signum())
+    cmds.iter()
+        .for_each(|cmd| iterate_moves(*cmd, &mut rope, &mut tail_map));
+
+    tail_map.len()
 }
 
-fn cmd_to_end_point(cmd: Cmd, start: (i32, i32)) -> (i32, i32) {
+fn cmd_to_move(cmd: Cmd) -> (Point2, i32) {
     match cmd {
-        Cmd::Up(move_size) => (start.0, start.1 + move_size),
-        Cmd::Right(move_size) => (start.0 + move_size, start.1),
-        Cmd::Left(move_size) => (start.0 - move_size, start.1),
-        Cmd::Down(move_size) => (start.0, start.1 - move_size),
+        Cmd::Up(move_size) => (Point2::new(0, 1), move_size),
+        Cmd::Right(move_size) => (Point2::new(1, 0), move_size),
+        Cmd::Left(move_size) => (Point2::new(-1, 0), move_size),
+        Cmd::Down(move_size) => (Point2::new(0, -1), move_size),
     }
 }
 
-fn iterate_moves(cmd: Cmd, rope: &mut Vec<(i32, i32)>, tail_map: &mut HashSet<(i32, i32)>) {
-    let head_end = cmd_to_end_point(cmd, rope[0]);
+fn iterate_moves(cmd: Cmd, rope: &mut [Point2], tail_map: &mut HashSet<Point2>) {
+    let (move_step, move_size) = cmd_to_move(cmd);
 
-    while rope[0] != head_end {
-        let move_step = cmd_to_move_step(cmd);
-        rope[0] = (rope[0].0 + move_step.0, rope[0].1 + move_step.1);
+    for _ in 0..move_size {
+        rope[0] += move_step;
         for knot_idx in 0..(rope.len() - 1) {
-            if !check_adjacent(rope[knot_idx], rope[knot_idx + 1]) {
-                rope[knot_idx + 1] = calc_tail_pos(rope[knot_idx], rope[knot_idx + 1]);
+            // Knots touching, diagonals included, don't pull the next one along
+            if rope[knot_idx].chebyshev(rope[knot_idx + 1]) > 1 {
+                rope[knot_idx + 1] = rope[knot_idx + 1].step_toward(rope[knot_idx]);
             }
         }
 
@@ -105,25 +105,7 @@ fn iterate_moves(cmd: Cmd, rope: &mut Vec<(i32, i32)>, tail_map: &mut HashSet<(i
     }
 }
 
-fn check_adjacent(head_pos: (i32, i32), tail_pos: (i32, i32)) -> bool {
-    let dirs = [
-        (-1, -1),
-        (1, -1),
-        (-1, 1),
-        (1, 1),
-        (1, 0),
-        (-1, 0),
-        (0, 1),
-        (0, -1),
-        (0, 0),
-    ];
-
-    dirs.iter()
-        .any(|(x, y)| (head_pos.0 + x, head_pos.1 + y) == tail_pos)
-}
-
-fn parse() -> Result<Vec<Cmd>> {
-    let input = std::fs::read_to_string("input/day_9.txt")?;
-
-    input.lines().map(Cmd::try_from).collect()
+/// Parses one move per line.
+pub fn parse(input: &str) -> ParseResult<Vec<Cmd>> {
+    parse::lines(input).map(Cmd::try_from).collect()
 }
@@ -0,0 +1,51 @@
+//! Downloading puzzle inputs.
+
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use crate::config::Config;
+use crate::http::HttpClient;
+
+/// What [`fetch_input`] did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchOutcome {
+    /// The input file was already there and was left alone.
+    Exists,
+    /// The input was downloaded, or taken from the cache, and written out.
+    Written,
+}
+
+/// Writes day `day`'s input to `path` unless the file already exists. An empty file, such as
+/// the stub `aoc new-day` creates, counts as missing.
+pub fn fetch_input(
+    client: &dyn HttpClient,
+    config: &Config,
+    day: u32,
+    path: impl AsRef<Path>,
+) -> Result<FetchOutcome> {
+    let path = path.as_ref();
+    if path.metadata().is_ok_and(|metadata| metadata.len() > 0) {
+        return Ok(FetchOutcome::Exists);
+    }
+
+    let url = config.input_url(day);
+    let response = client.get(&url)?;
+    if !response.is_success() {
+        bail!(
+            "Fetching {} failed with status {}: {}",
+            url,
+            response.status,
+            response.body.trim()
+        );
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    std::fs::write(path, response.body)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    Ok(FetchOutcome::Written)
+}
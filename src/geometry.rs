@@ -0,0 +1,284 @@
+//! Integer points and vectors in 2 and 3 dimensions, and bounding boxes around them.
+
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A point, or the vector between two points, on the integer plane.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point2 {
+    /// Horizontal coordinate.
+    pub x: i64,
+    /// Vertical coordinate.
+    pub y: i64,
+}
+
+/// A point, or the vector between two points, in integer 3D space.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point3 {
+    /// First coordinate.
+    pub x: i64,
+    /// Second coordinate.
+    pub y: i64,
+    /// Third coordinate.
+    pub z: i64,
+}
+
+/// Component-wise operations shared by [`Point2`] and [`Point3`].
+pub trait Coords: Copy + Add<Output = Self> + Sub<Output = Self> {
+    /// Each component's absolute value summed: the taxicab length.
+    fn manhattan_len(self) -> i64;
+    /// Largest component's absolute value: the length when diagonal moves are allowed.
+    fn chebyshev_len(self) -> i64;
+    /// Each component replaced by its sign, a unit step along every axis that isn't 0.
+    fn signum(self) -> Self;
+    /// Smallest of each component.
+    fn min_each(self, other: Self) -> Self;
+    /// Largest of each component.
+    fn max_each(self, other: Self) -> Self;
+    /// Whether every component is at most `other`'s.
+    fn all_le(self, other: Self) -> bool;
+
+    /// Manhattan distance to `other`.
+    fn manhattan(self, other: Self) -> i64 {
+        (other - self).manhattan_len()
+    }
+
+    /// Chebyshev distance to `other`. Points touching diagonally are 1 apart.
+    fn chebyshev(self, other: Self) -> i64 {
+        (other - self).chebyshev_len()
+    }
+
+    /// One step towards `target`, moving at most 1 along each axis.
+    fn step_toward(self, target: Self) -> Self {
+        self + (target - self).signum()
+    }
+}
+
+macro_rules! impl_coords {
+    ($point:ident { $($axis:ident),+ }) => {
+        impl $point {
+            /// Point from its coordinates.
+            pub const fn new($($axis: i64),+) -> Self {
+                Self { $($axis),+ }
+            }
+        }
+
+        impl Coords for $point {
+            fn manhattan_len(self) -> i64 {
+                0 $(+ self.$axis.abs())+
+            }
+
+            fn chebyshev_len(self) -> i64 {
+                0 $(.max(self.$axis.abs()))+
+            }
+
+            fn signum(self) -> Self {
+                Self { $($axis: self.$axis.signum()),+ }
+            }
+
+            fn min_each(self, other: Self) -> Self {
+                Self { $($axis: self.$axis.min(other.$axis)),+ }
+            }
+
+            fn max_each(self, other: Self) -> Self {
+                Self { $($axis: self.$axis.max(other.$axis)),+ }
+            }
+
+            fn all_le(self, other: Self) -> bool {
+                true $(&& self.$axis <= other.$axis)+
+            }
+        }
+
+        impl Add for $point {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self::Output {
+                Self { $($axis: self.$axis + rhs.$axis),+ }
+            }
+        }
+
+        impl Sub for $point {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self::Output {
+                Self { $($axis: self.$axis - rhs.$axis),+ }
+            }
+        }
+
+        impl Mul<i64> for $point {
+            type Output = Self;
+
+            fn mul(self, rhs: i64) -> Self::Output {
+                Self { $($axis: self.$axis * rhs),+ }
+            }
+        }
+
+        impl Neg for $point {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                Self { $($axis: -self.$axis),+ }
+            }
+        }
+
+        impl AddAssign for $point {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl SubAssign for $point {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+    };
+}
+
+impl_coords!(Point2 { x, y });
+impl_coords!(Point3 { x, y, z });
+
+impl Point2 {
+    /// `(0, 0)`.
+    pub const ORIGIN: Self = Self::new(0, 0);
+    /// Unit steps along each axis: +x, +y, -x, -y. With y growing downward, as in
+    /// [`crate::grid`], that's right, down, left, up, the same order as
+    /// [`crate::grid::ORTHOGONAL`].
+    pub const ORTHOGONAL: [Self; 4] = [
+        Self::new(1, 0),
+        Self::new(0, 1),
+        Self::new(-1, 0),
+        Self::new(0, -1),
+    ];
+    /// Unit steps along the diagonals.
+    pub const DIAGONAL: [Self; 4] = [
+        Self::new(1, 1),
+        Self::new(-1, 1),
+        Self::new(-1, -1),
+        Self::new(1, -1),
+    ];
+
+    /// The 4 points sharing an edge with this one.
+    pub fn neighbors4(self) -> impl Iterator<Item = Self> {
+        Self::ORTHOGONAL.into_iter().map(move |step| self + step)
+    }
+
+    /// The 8 points touching this one, diagonals included.
+    pub fn neighbors8(self) -> impl Iterator<Item = Self> {
+        Self::ORTHOGONAL
+            .into_iter()
+            .chain(Self::DIAGONAL)
+            .map(move |step| self + step)
+    }
+}
+
+impl Point3 {
+    /// `(0, 0, 0)`.
+    pub const ORIGIN: Self = Self::new(0, 0, 0);
+    /// Unit steps along each axis, both ways.
+    pub const FACES: [Self; 6] = [
+        Self::new(1, 0, 0),
+        Self::new(-1, 0, 0),
+        Self::new(0, 1, 0),
+        Self::new(0, -1, 0),
+        Self::new(0, 0, 1),
+        Self::new(0, 0, -1),
+    ];
+
+    /// The 6 points sharing a face with this one.
+    pub fn neighbors6(self) -> impl Iterator<Item = Self> {
+        Self::FACES.into_iter().map(move |step| self + step)
+    }
+
+    /// The 26 points touching this one by a face, edge or corner.
+    pub fn neighbors26(self) -> impl Iterator<Item = Self> {
+        (-1..=1)
+            .flat_map(|x| (-1..=1).flat_map(move |y| (-1..=1).map(move |z| Self::new(x, y, z))))
+            .filter(|step| *step != Self::ORIGIN)
+            .map(move |step| self + step)
+    }
+}
+
+/// Axis aligned box from `min` to `max`, both included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds<P> {
+    /// Corner with the smallest coordinates.
+    pub min: P,
+    /// Corner with the largest coordinates.
+    pub max: P,
+}
+
+impl<P: Coords> Bounds<P> {
+    /// Box holding just `point`.
+    pub fn new(point: P) -> Self {
+        Self {
+            min: point,
+            max: point,
+        }
+    }
+
+    /// Smallest box holding every point, or `None` without any.
+    pub fn from_points(points: impl IntoIterator<Item = P>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let mut bounds = Self::new(points.next()?);
+        points.for_each(|point| bounds.extend(point));
+
+        Some(bounds)
+    }
+
+    /// Grows the box to hold `point`.
+    pub fn extend(&mut self, point: P) {
+        self.min = self.min.min_each(point);
+        self.max = self.max.max_each(point);
+    }
+
+    /// Whether `point` is inside the box or on its edge.
+    pub fn contains(&self, point: P) -> bool {
+        self.min.all_le(point) && point.all_le(self.max)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_point2() {
+        let a = Point2::new(2, 18);
+        let b = Point2::new(-2, 15);
+
+        assert_eq!(a - b, Point2::new(4, 3));
+        assert_eq!(a + b * 2, Point2::new(-2, 48));
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.chebyshev(b), 4);
+        assert_eq!(b.step_toward(a), Point2::new(-1, 16));
+        assert_eq!(a.step_toward(a), a);
+        assert_eq!(Point2::ORIGIN.neighbors8().count(), 8);
+        assert!(Point2::ORIGIN
+            .neighbors4()
+            .all(|point| point.manhattan(Point2::ORIGIN) == 1));
+    }
+
+    #[test]
+    fn test_point3() {
+        let cube = Point3::new(2, 2, 2);
+
+        assert_eq!(cube.neighbors6().count(), 6);
+        assert!(cube.neighbors6().all(|point| point.manhattan(cube) == 1));
+        assert_eq!(cube.neighbors26().count(), 26);
+        assert!(cube.neighbors26().all(|point| point.chebyshev(cube) == 1));
+        assert_eq!(-cube, Point3::new(-2, -2, -2));
+    }
+
+    #[test]
+    fn test_bounds() {
+        let bounds =
+            Bounds::from_points([Point2::new(3, -1), Point2::new(-2, 4), Point2::new(0, 0)])
+                .unwrap();
+
+        assert_eq!(bounds.min, Point2::new(-2, -1));
+        assert_eq!(bounds.max, Point2::new(3, 4));
+        assert!(bounds.contains(Point2::new(3, 4)));
+        assert!(!bounds.contains(Point2::new(4, 0)));
+        assert_eq!(Bounds::<Point3>::from_points([]), None);
+    }
+}
@@ -0,0 +1,225 @@
+//! A rectangular grid with flat row-major storage, shared by the grid based days.
+//!
+//! Cells are addressed by `(x, y)`, `x` being the column and `y` the row counted from the top.
+
+use std::ops::{Index, IndexMut};
+
+use crate::parse::{self, ParseResult};
+
+/// Offsets to the 4 orthogonal neighbors: right, down, left, up, as y grows downward.
+pub const ORTHOGONAL: [(isize, isize); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+/// Offsets to all 8 neighbors, diagonals included.
+pub const ALL_DIRECTIONS: [(isize, isize); 8] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+
+/// A `width` by `height` grid of `T`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// Grid with every cell set to `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Parses one row per line, mapping each character with `cell`. Characters it rejects are
+    /// reported as an invalid `what`, and every row must be as long as the first.
+    pub fn parse(
+        input: &str,
+        what: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> ParseResult<Self> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+
+        for line in parse::lines(input) {
+            let start = cells.len();
+            for (idx, c) in line.text.char_indices() {
+                let value = cell(c).ok_or_else(|| {
+                    line.error(
+                        &line.text[idx..idx + c.len_utf8()],
+                        format!("Invalid {}", what),
+                    )
+                })?;
+                cells.push(value);
+            }
+
+            let row_width = cells.len() - start;
+            match width {
+                Some(width) if width != row_width => {
+                    return Err(line.error(
+                        line.text,
+                        format!("Expected {} columns, found {}", width, row_width),
+                    ));
+                }
+                _ => width = Some(row_width),
+            }
+            height += 1;
+        }
+
+        Ok(Self {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    /// Number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The cell at `(x, y)`, if it's inside the grid.
+    pub fn get(&self, (x, y): (usize, usize)) -> Option<&T> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    /// Mutable cell at `(x, y)`, if it's inside the grid.
+    pub fn get_mut(&mut self, (x, y): (usize, usize)) -> Option<&mut T> {
+        (x < self.width && y < self.height).then(|| &mut self.cells[y * self.width + x])
+    }
+
+    /// `pos` moved by `(dx, dy)`, if that's still inside the grid.
+    pub fn offset(
+        &self,
+        (x, y): (usize, usize),
+        (dx, dy): (isize, isize),
+    ) -> Option<(usize, usize)> {
+        let x = x.checked_add_signed(dx).filter(|x| *x < self.width)?;
+        let y = y.checked_add_signed(dy).filter(|y| *y < self.height)?;
+
+        Some((x, y))
+    }
+
+    /// Orthogonal neighbors of `pos` inside the grid.
+    pub fn neighbors4(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        ORTHOGONAL
+            .into_iter()
+            .filter_map(move |offset| self.offset(pos, offset))
+    }
+
+    /// Orthogonal and diagonal neighbors of `pos` inside the grid.
+    pub fn neighbors8(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        ALL_DIRECTIONS
+            .into_iter()
+            .filter_map(move |offset| self.offset(pos, offset))
+    }
+
+    /// Row `y`, left to right.
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// Column `x`, top to bottom.
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        (0..self.height).map(move |y| &self[(x, y)])
+    }
+
+    /// Every row, top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Every position along with its cell, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> + '_ {
+        self.positions().zip(&self.cells)
+    }
+
+    /// Draws the grid one line per row, each cell drawn by `cell`.
+    pub fn render(&self, cell: impl Fn(&T) -> char) -> String {
+        let mut render = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            render.extend(row.iter().map(&cell));
+            render.push('\n');
+        }
+
+        render
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: (usize, usize)) -> &Self::Output {
+        let (width, height) = (self.width, self.height);
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{:?} outside the {}x{} grid", pos, width, height))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, pos: (usize, usize)) -> &mut Self::Output {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{:?} outside the {}x{} grid", pos, width, height))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+
+    use super::*;
+
+    #[test]
+    fn test_grid() -> Result<()> {
+        let grid = Grid::parse("123\n456\n", "digit", |c| c.to_digit(10))?;
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 0)], 3);
+        assert_eq!(grid.get((0, 1)), Some(&4));
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.row(1), &[4, 5, 6]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![2, 5]);
+        assert_eq!(grid.offset((0, 0), (-1, 0)), None);
+        assert_eq!(grid.offset((0, 0), (2, 1)), Some((2, 1)));
+
+        let mut neighbors = grid.neighbors4((0, 0)).collect::<Vec<_>>();
+        neighbors.sort();
+        assert_eq!(neighbors, vec![(0, 1), (1, 0)]);
+        assert_eq!(grid.neighbors8((1, 0)).count(), 5);
+        assert_eq!(grid.neighbors8((1, 1)).count(), 5);
+
+        assert_eq!(
+            grid.render(|digit| char::from_digit(*digit, 10).unwrap()),
+            "123\n456\n"
+        );
+
+        let err = Grid::parse("123\n4x6\n", "digit", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        let err = Grid::parse("123\n45\n", "digit", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(err.message, "Expected 3 columns, found 2");
+
+        Ok(())
+    }
+}
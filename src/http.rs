@@ -0,0 +1,165 @@
+//! The HTTP layer used to talk to the site, behind a trait so it can be swapped out in tests.
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+
+/// Sent with every request, as the site asks automated tools to identify themselves.
+const USER_AGENT: &str = "advent-of-code-2022 aoc CLI";
+
+/// Status code and body of a response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    /// HTTP status code.
+    pub status: u16,
+    /// Response body.
+    pub body: String,
+}
+
+impl Response {
+    /// Whether the status is 2xx.
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// The requests the site helpers make.
+pub trait HttpClient {
+    /// Sends a GET request for `url`.
+    fn get(&self, url: &str) -> Result<Response>;
+
+    /// POSTs `form` to `url` as `application/x-www-form-urlencoded`.
+    fn post_form(&self, url: &str, form: &[(&str, &str)]) -> Result<Response>;
+}
+
+/// [`HttpClient`] sending real requests, logged in with a session cookie.
+pub struct UreqClient {
+    agent: ureq::Agent,
+    session: Option<String>,
+}
+
+impl UreqClient {
+    /// Client using `session` as its cookie. Requests fail without one.
+    pub fn new(session: Option<String>) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .build()
+            .into();
+
+        Self { agent, session }
+    }
+
+    fn cookie(&self) -> Result<String> {
+        match &self.session {
+            Some(session) => Ok(format!("session={}", session)),
+            None => bail!(
+                "No session token, set {} or `session` in {}",
+                crate::config::SESSION_VAR,
+                crate::config::CONFIG_PATH
+            ),
+        }
+    }
+}
+
+impl HttpClient for UreqClient {
+    fn get(&self, url: &str) -> Result<Response> {
+        let mut response = self
+            .agent
+            .get(url)
+            .header("Cookie", self.cookie()?)
+            .header("User-Agent", USER_AGENT)
+            .call()
+            .with_context(|| format!("GET {} failed", url))?;
+
+        Ok(Response {
+            status: response.status().as_u16(),
+            body: response.body_mut().read_to_string()?,
+        })
+    }
+
+    fn post_form(&self, url: &str, form: &[(&str, &str)]) -> Result<Response> {
+        let mut response = self
+            .agent
+            .post(url)
+            .header("Cookie", self.cookie()?)
+            .header("User-Agent", USER_AGENT)
+            .send_form(form.iter().copied())
+            .with_context(|| format!("POST {} failed", url))?;
+
+        Ok(Response {
+            status: response.status().as_u16(),
+            body: response.body_mut().read_to_string()?,
+        })
+    }
+}
+
+/// Wraps a client and keeps successful GET responses under `dir`, so each URL only ever
+/// reaches the server once per session. POSTs always go through.
+pub struct CachedClient<C> {
+    inner: C,
+    dir: PathBuf,
+}
+
+impl<C: HttpClient> CachedClient<C> {
+    /// Caches `inner`'s responses under `dir`, in a subdirectory for the session `inner` is
+    /// logged in with. Inputs differ per account, so switching sessions never reuses another
+    /// account's responses.
+    pub fn new(inner: C, dir: impl Into<PathBuf>, session: Option<&str>) -> Self {
+        Self {
+            inner,
+            dir: dir.into().join(session_key(session)),
+        }
+    }
+
+    /// Cache file for a URL: its host and path under the cache directory.
+    fn cache_path(&self, url: &str) -> PathBuf {
+        let key = url.split_once("://").map_or(url, |(_, rest)| rest);
+        let key = key.replace(
+            |c: char| !(c.is_ascii_alphanumeric() || "/.-_".contains(c)),
+            "_",
+        );
+
+        self.dir.join(key.trim_matches('/'))
+    }
+}
+
+/// Directory name for a session: a hash of the token rather than the token itself, so the
+/// cache doesn't hold the credential. FNV-1a, as it has to stay stable across builds.
+fn session_key(session: Option<&str>) -> String {
+    let Some(session) = session else {
+        return "anonymous".to_string();
+    };
+
+    let hash = session.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+    format!("session-{:016x}", hash)
+}
+
+impl<C: HttpClient> HttpClient for CachedClient<C> {
+    fn get(&self, url: &str) -> Result<Response> {
+        let path = self.cache_path(url);
+        if path.exists() {
+            let body = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+
+            return Ok(Response { status: 200, body });
+        }
+
+        let response = self.inner.get(url)?;
+        if response.is_success() {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+            std::fs::write(&path, &response.body)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+
+        Ok(response)
+    }
+
+    fn post_form(&self, url: &str, form: &[(&str, &str)]) -> Result<Response> {
+        self.inner.post_form(url, form)
+    }
+}
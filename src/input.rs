@@ -0,0 +1,105 @@
+//! Puzzle input sources.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{Context, Error, Result};
+
+/// Where a day reads its puzzle input from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum InputSource {
+    /// The day's checked in `input/day_N.txt`
+    #[default]
+    Default,
+    /// A file on disk.
+    Path(PathBuf),
+    /// Standard input, given as `-` on the command line.
+    Stdin,
+}
+
+impl InputSource {
+    /// Name used when reporting results for this input.
+    pub fn name(&self, default_path: &str) -> String {
+        match self {
+            InputSource::Default => default_path.to_string(),
+            InputSource::Path(path) => path.display().to_string(),
+            InputSource::Stdin => "-".to_string(),
+        }
+    }
+
+    /// Reads the whole input, falling back to `default_path` for [`InputSource::Default`].
+    pub fn read(&self, default_path: &str) -> Result<String> {
+        match self {
+            InputSource::Default => std::fs::read_to_string(default_path)
+                .with_context(|| format!("Failed to read input {}", default_path)),
+            InputSource::Path(path) => std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read input {}", path.display())),
+            InputSource::Stdin => {
+                let mut input = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut input)
+                    .context("Failed to read input from stdin")?;
+
+                Ok(input)
+            }
+        }
+    }
+
+    /// Opens the input for reading a line at a time rather than all at once, falling back to
+    /// `default_path` for [`InputSource::Default`].
+    pub fn open(&self, default_path: &str) -> Result<Box<dyn BufRead>> {
+        let open = |path: &Path| {
+            File::open(path).with_context(|| format!("Failed to read input {}", path.display()))
+        };
+
+        match self {
+            InputSource::Default => Ok(Box::new(BufReader::new(open(Path::new(default_path))?))),
+            InputSource::Path(path) => Ok(Box::new(BufReader::new(open(path)?))),
+            InputSource::Stdin => Ok(Box::new(std::io::stdin().lock())),
+        }
+    }
+}
+
+impl FromStr for InputSource {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "-" => Ok(InputSource::Stdin),
+            path => Ok(InputSource::Path(PathBuf::from(path))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_input_source() -> Result<()> {
+        assert_eq!("-".parse::<InputSource>()?, InputSource::Stdin);
+        assert_eq!(
+            "input/day_4.txt".parse::<InputSource>()?,
+            InputSource::Path(PathBuf::from("input/day_4.txt"))
+        );
+
+        let default = InputSource::Default.read("input/day_18_test.txt")?;
+        let path = "input/day_18_test.txt"
+            .parse::<InputSource>()?
+            .read("input/day_18.txt")?;
+        assert_eq!(default, path);
+
+        assert!(InputSource::Default.read("input/day_0.txt").is_err());
+
+        let mut opened = String::new();
+        InputSource::Default
+            .open("input/day_18_test.txt")?
+            .read_to_string(&mut opened)?;
+        assert_eq!(opened, default);
+        assert!(InputSource::Default.open("input/day_0.txt").is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+//! Advent of Code 2022 solutions.
+//!
+//! Every day lives in its own `day_N` module exposing the parsed puzzle model, a `parse`
+//! function, the `day_N_1`/`day_N_2` solvers and a `DayN` type implementing
+//! [`solution::Solution`]. [`registry::SOLUTIONS`] lists every implemented day for tooling
+//! that wants to run them generically.
+
+#![warn(missing_docs)]
+
+pub mod answer;
+pub mod config;
+pub mod day_1;
+pub mod day_10;
+pub mod day_11;
+pub mod day_12;
+pub mod day_13;
+pub mod day_14;
+pub mod day_15;
+pub mod day_16;
+pub mod day_18;
+pub mod day_2;
+pub mod day_3;
+pub mod day_4;
+pub mod day_5;
+pub mod day_6;
+pub mod day_7;
+pub mod day_8;
+pub mod day_9;
+pub mod fetch;
+pub mod geometry;
+pub mod grid;
+pub mod http;
+pub mod input;
+pub mod parse;
+pub mod registry;
+pub mod report;
+pub mod scaffold;
+pub mod solution;
+pub mod submit;
+pub mod timing;
+pub mod verify;
+pub mod watch;
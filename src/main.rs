@@ -1,65 +1,447 @@
-mod day_1;
-mod day_10;
-mod day_11;
-mod day_12;
-mod day_13;
-mod day_14;
-mod day_15;
-mod day_16;
-mod day_18;
-mod day_2;
-mod day_3;
-mod day_4;
-mod day_5;
-mod day_6;
-mod day_7;
-mod day_8;
-mod day_9;
-
-use anyhow::Result;
+mod cli;
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::process::ExitCode;
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use rayon::prelude::*;
 use tracing::Level;
+use tracing_chrome::ChromeLayerBuilder;
+use tracing_flame::FlameLayer;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
 
-use day_1::day_1;
-use day_10::day_10;
-use day_11::day_11;
-use day_12::day_12;
-use day_13::day_13;
-use day_14::day_14;
-use day_15::day_15;
-use day_16::day_16;
-use day_18::day_18;
-use day_2::day_2;
-use day_3::day_3;
-use day_4::day_4;
-use day_5::day_5;
-use day_6::day_6;
-use day_7::day_7;
-use day_8::day_8;
-use day_9::day_9;
-
-fn main() -> Result<()> {
-    tracing_subscriber::fmt::init();
+use advent_of_code_2022::answer::Answer;
+use advent_of_code_2022::config::{self, Config};
+use advent_of_code_2022::day_1::{self, Day1};
+use advent_of_code_2022::day_2::{self, Day2, Game};
+use advent_of_code_2022::fetch::{self, FetchOutcome};
+use advent_of_code_2022::http::{CachedClient, UreqClient};
+use advent_of_code_2022::input::InputSource;
+use advent_of_code_2022::parse::ParseError;
+use advent_of_code_2022::registry;
+use advent_of_code_2022::report::PartRecord;
+use advent_of_code_2022::scaffold;
+use advent_of_code_2022::solution::{DayRun, DynSolution};
+use advent_of_code_2022::submit::{self, SubmissionLog, Verdict};
+use advent_of_code_2022::timing;
+use advent_of_code_2022::verify::{self, AnswerFile, DayCheck, VerifySummary};
+use advent_of_code_2022::watch::{self, FileWatcher};
+use cli::{
+    Cli, Command, DaySelection, ElfStatsArgs, FetchArgs, GameKind, NewDayArgs, OutputFormat,
+    RunArgs, StrategyArgs, SubmitArgs, TraceFormat, VerifyArgs, WatchArgs,
+};
 
-    let root = tracing::span!(Level::INFO, "Root");
-    let _enter = root.enter();
+fn print_answer(day: u32, part: u8, answer: &Answer) {
+    match answer {
+        Answer::Render(render) => print!("Day {}-{}:\n{}", day, part, render),
+        answer => println!("Day {}-{}: {}", day, part, answer),
+    }
+}
+
+fn exit_code(failed: bool) -> ExitCode {
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn select(args: &RunArgs) -> Result<(Vec<&'static dyn DynSolution>, InputSource)> {
+    let selected = registry::select(args.days.as_ref().map(DaySelection::days))?;
+
+    let input = match &args.input {
+        Some(_) if selected.len() != 1 => {
+            bail!("--input can only be used when running a single day")
+        }
+        Some(input) => input.clone(),
+        None => InputSource::Default,
+    };
+
+    Ok((selected, input))
+}
+
+fn print_text(day_run: &DayRun, time: bool) {
+    if time {
+        println!(
+            "Day {} parse: {}",
+            day_run.day,
+            timing::format_duration(day_run.parse)
+        );
+    }
+
+    for part_run in &day_run.parts {
+        match &part_run.answer {
+            Ok(answer) => print_answer(day_run.day, part_run.part, answer),
+            Err(e) => eprintln!("Day {}-{} failed: {:?}", day_run.day, part_run.part, e),
+        }
+
+        if time {
+            println!(
+                "Day {}-{} time: {}",
+                day_run.day,
+                part_run.part,
+                timing::format_duration(part_run.elapsed)
+            );
+        }
+    }
+}
 
-    day_1()?;
-    day_2()?;
-    day_3()?;
-    day_4()?;
-    day_5()?;
-    day_6()?;
-    day_7()?;
-    day_8()?;
-    day_9()?;
-    day_10()?;
-    day_11()?;
-    day_12()?;
-    day_13()?;
-    day_14()?;
-    day_15()?;
-    day_16()?;
-    day_18()?;
+fn print_json(records: Vec<PartRecord>) -> Result<()> {
+    for record in records {
+        println!("{}", serde_json::to_string(&record)?);
+    }
 
     Ok(())
 }
+
+type DayResult = (&'static dyn DynSolution, Result<DayRun>);
+
+// Sequential runs stream results as each day finishes. Parallel runs wait for the whole pool so
+// results still come out in day order.
+fn run_days<'a>(
+    selected: Vec<&'static dyn DynSolution>,
+    part: Option<u8>,
+    input: &'a InputSource,
+    jobs: Option<usize>,
+) -> Result<Box<dyn Iterator<Item = DayResult> + 'a>> {
+    match jobs {
+        None => {
+            Ok(Box::new(selected.into_iter().map(move |solution| {
+                (solution, solution.run(part, input))
+            })))
+        }
+        Some(jobs) => {
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(jobs).build()?;
+            let results = pool.install(|| {
+                selected
+                    .into_par_iter()
+                    .map(|solution| (solution, solution.run(part, input)))
+                    .collect::<Vec<_>>()
+            });
+
+            Ok(Box::new(results.into_iter()))
+        }
+    }
+}
+
+fn run(args: RunArgs) -> Result<ExitCode> {
+    let (selected, input) = select(&args)?;
+
+    let mut failed = false;
+    let mut runs = Vec::new();
+    for (solution, result) in run_days(selected, args.part, &input, args.jobs)? {
+        match result {
+            Ok(day_run) => {
+                failed |= day_run
+                    .parts
+                    .iter()
+                    .any(|part_run| part_run.answer.is_err());
+
+                match args.format {
+                    OutputFormat::Text => print_text(&day_run, args.time),
+                    OutputFormat::Json => print_json(PartRecord::from_run(&day_run))?,
+                }
+
+                runs.push(day_run);
+            }
+            Err(e) => {
+                failed = true;
+
+                match args.format {
+                    OutputFormat::Text => eprintln!("Day {} failed: {:?}", solution.day(), e),
+                    OutputFormat::Json => {
+                        print_json(PartRecord::failed(solution, args.part, &input, &e))?
+                    }
+                }
+            }
+        }
+    }
+
+    if args.time && args.format == OutputFormat::Text {
+        print!("\n{}", timing::summary_table(&runs));
+    }
+
+    Ok(exit_code(failed))
+}
+
+fn record(args: RunArgs) -> Result<ExitCode> {
+    let (selected, input) = select(&args)?;
+    let mut answers = AnswerFile::load(verify::ANSWERS_PATH)?;
+
+    let mut failed = false;
+    for solution in selected {
+        let input_name = verify::input_name(solution, &input)?;
+        match solution.run(args.part, &input) {
+            Ok(day_run) => {
+                print_text(&day_run, false);
+                for part_run in day_run.parts {
+                    match part_run.answer {
+                        Ok(answer) => {
+                            answers.record(solution.day(), &input_name, part_run.part, &answer)?
+                        }
+                        Err(_) => failed = true,
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("Day {} failed: {:?}", solution.day(), e);
+                failed = true;
+            }
+        }
+    }
+
+    answers.save(verify::ANSWERS_PATH)?;
+
+    Ok(exit_code(failed))
+}
+
+fn verify(args: VerifyArgs) -> Result<ExitCode> {
+    let selected = registry::select(args.days.as_ref().map(DaySelection::days))?;
+    let answers = AnswerFile::load(verify::ANSWERS_PATH)?;
+
+    let day_checks = verify::verify(&selected, &answers);
+    for day_check in &day_checks {
+        match day_check {
+            DayCheck::Unrecorded(day) => println!("Day {}: no recorded answers", day),
+            DayCheck::Input {
+                day,
+                input,
+                checks: Err(e),
+            } => println!("Day {} ({}): FAILED {:?}", day, input, e),
+            DayCheck::Input {
+                day,
+                input,
+                checks: Ok(checks),
+            } => {
+                for (part, check) in checks {
+                    println!("Day {}-{} ({}): {}", day, part, input, check);
+                }
+            }
+        }
+    }
+
+    let summary = VerifySummary::new(&day_checks);
+    println!(
+        "{} passed, {} failed, {} unrecorded",
+        summary.passed, summary.failed, summary.unrecorded
+    );
+
+    Ok(exit_code(summary.failed > 0))
+}
+
+fn fetch(args: FetchArgs) -> Result<ExitCode> {
+    let config = Config::load(config::CONFIG_PATH)?;
+    let client = CachedClient::new(
+        UreqClient::new(config.session.clone()),
+        &config.cache_dir,
+        config.session.as_deref(),
+    );
+
+    for day in args.days.days() {
+        let path = Path::new(verify::INPUT_DIR).join(format!("day_{}.txt", day));
+        match fetch::fetch_input(&client, &config, day, &path)? {
+            FetchOutcome::Exists => println!("Day {}: {} already exists", day, path.display()),
+            FetchOutcome::Written => println!("Day {}: wrote {}", day, path.display()),
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn submit(args: SubmitArgs) -> Result<ExitCode> {
+    let (day, part) = (args.day, args.part);
+    let computed = args.answer.is_none();
+
+    let answer = match args.answer {
+        Some(answer) => answer,
+        None => {
+            let solution =
+                registry::find(day).with_context(|| format!("Day {} is not implemented", day))?;
+            let day_run = solution.run(Some(part), &InputSource::Default)?;
+            let part_run = day_run
+                .parts
+                .into_iter()
+                .next()
+                .with_context(|| format!("Day {} part {} didn't run", day, part))?;
+
+            match part_run.answer? {
+                Answer::Render(render) => bail!(
+                    "Day {}-{} is a render, pass the letters with --answer:\n{}",
+                    day,
+                    part,
+                    render
+                ),
+                answer => answer.to_string(),
+            }
+        }
+    };
+
+    let config = Config::load(config::CONFIG_PATH)?;
+    let client = UreqClient::new(config.session.clone());
+    let mut log = SubmissionLog::load(submit::LOG_PATH)?;
+
+    let submission = submit::submit(&client, &config, &mut log, day, part, &answer)?;
+    println!(
+        "Day {}-{}: {} ({:?})\n{}",
+        day, part, answer, submission.verdict, submission.response
+    );
+
+    // The site judges the real input, so that's the one the accepted answer belongs to
+    if submission.verdict == Verdict::Correct && computed {
+        let mut answers = AnswerFile::load(verify::ANSWERS_PATH)?;
+        answers.record(
+            day,
+            &format!("day_{}.txt", day),
+            part,
+            &Answer::from(answer),
+        )?;
+        answers.save(verify::ANSWERS_PATH)?;
+    }
+
+    Ok(exit_code(submission.verdict != Verdict::Correct))
+}
+
+fn new_day(args: NewDayArgs) -> Result<ExitCode> {
+    for path in scaffold::new_day(".", args.day)? {
+        println!("Wrote {}", path.display());
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn watch(args: WatchArgs) -> Result<ExitCode> {
+    let day = args.day;
+    let solution =
+        registry::find(day).with_context(|| format!("Day {} is not implemented", day))?;
+    let inputs = watch::inputs(day);
+    println!(
+        "Watching {} and {}, press Ctrl-C to stop",
+        inputs[0].display(),
+        inputs[1].display()
+    );
+
+    let mut watcher = FileWatcher::new(inputs.clone());
+    let mut previous: Option<String> = None;
+    loop {
+        if watcher.poll() {
+            let output = watch::render(solution, &inputs);
+            match &previous {
+                None => print!("{}", output),
+                Some(previous) if *previous == output => println!("No change"),
+                Some(previous) => {
+                    println!(
+                        "--- {}",
+                        humantime::format_rfc3339_seconds(std::time::SystemTime::now())
+                    );
+                    print!("{}", watch::diff(previous, &output));
+                }
+            }
+            previous = Some(output);
+        }
+
+        std::thread::sleep(*args.interval);
+    }
+}
+
+fn elf_stats(args: ElfStatsArgs) -> Result<ExitCode> {
+    let input = args.input.unwrap_or_default();
+    let default_path = Day1.input_path();
+    let input_name = input.name(&default_path);
+    let elves = day_1::read_elves(input.open(&default_path)?)
+        .collect::<Result<Vec<_>>>()
+        .map_err(|e| match e.downcast::<ParseError>() {
+            Ok(e) => e.with_file(&input_name).into(),
+            Err(e) => e,
+        })?;
+
+    println!("== {} ==", input_name);
+    print!("{}", day_1::report(&elves, args.bins.into()));
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn strategy(args: StrategyArgs) -> Result<ExitCode> {
+    let game = match args.game {
+        GameKind::Rps => Game::rps(),
+        GameKind::Rpsls => Game::rpsls(),
+    };
+    let input = args.input.unwrap_or_default();
+    let default_path = Day2.input_path();
+    let input_name = input.name(&default_path);
+    let guide = game
+        .parse_guide(&input.read(&default_path)?)
+        .map_err(|e| e.with_file(&input_name))?;
+
+    println!("== {} ==", input_name);
+    print!("{}", day_2::report(&game, &guide, args.plans)?);
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Flushes the trace file, if any, when dropped at the end of `main`.
+#[derive(Default)]
+struct TraceGuard {
+    _chrome: Option<tracing_chrome::FlushGuard>,
+    _flame: Option<tracing_flame::FlushGuard<BufWriter<File>>>,
+}
+
+// Log output keeps its usual level, the trace file gets every span including the debug level
+// ones around hot inner loops
+fn init_tracing(cli: &Cli) -> Result<TraceGuard> {
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_filter(LevelFilter::INFO));
+
+    let Some(path) = &cli.trace else {
+        subscriber.init();
+        return Ok(TraceGuard::default());
+    };
+
+    match cli.trace_format {
+        TraceFormat::Chrome => {
+            let (layer, guard) = ChromeLayerBuilder::new()
+                .file(path)
+                .include_args(true)
+                .build();
+            subscriber.with(layer).init();
+
+            Ok(TraceGuard {
+                _chrome: Some(guard),
+                ..TraceGuard::default()
+            })
+        }
+        TraceFormat::Flame => {
+            let (layer, guard) = FlameLayer::with_file(path)
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            subscriber.with(layer.with_threads_collapsed(true)).init();
+
+            Ok(TraceGuard {
+                _flame: Some(guard),
+                ..TraceGuard::default()
+            })
+        }
+    }
+}
+
+fn main() -> Result<ExitCode> {
+    let cli = Cli::parse();
+    let _trace = init_tracing(&cli)?;
+
+    let root = tracing::span!(Level::INFO, "Root");
+    let _enter = root.enter();
+
+    match cli.command {
+        Command::Run(args) => run(args),
+        Command::Record(args) => record(args),
+        Command::Verify(args) => verify(args),
+        Command::Fetch(args) => fetch(args),
+        Command::Submit(args) => submit(args),
+        Command::NewDay(args) => new_day(args),
+        Command::Watch(args) => watch(args),
+        Command::ElfStats(args) => elf_stats(args),
+        Command::Strategy(args) => strategy(args),
+    }
+}
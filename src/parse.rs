@@ -0,0 +1,187 @@
+//! The [`ParseError`] every day's parser reports, and line helpers for building it.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Result of parsing puzzle input.
+pub type ParseResult<T> = std::result::Result<T, ParseError>;
+
+/// Bad puzzle input, pinned to where it was found.
+///
+/// Parsers only see the input text, so `file` is filled in by the runner through
+/// [`ParseError::with_file`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Input the error was found in, if known.
+    pub file: Option<String>,
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column, in characters.
+    pub column: usize,
+    /// The offending text.
+    pub text: String,
+    /// What was wrong with it.
+    pub message: String,
+}
+
+impl ParseError {
+    /// Error at a line and column.
+    pub fn new(
+        line: usize,
+        column: usize,
+        text: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            file: None,
+            line,
+            column,
+            text: text.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Error for input that stopped after `line_count` lines while more was expected.
+    pub fn end_of_input(line_count: usize, message: impl Into<String>) -> Self {
+        Self::new(line_count + 1, 1, "", message)
+    }
+
+    /// Records the input the error came from.
+    pub fn with_file(mut self, file: impl Into<String>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let file = self.file.as_deref().unwrap_or("<input>");
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            file, self.line, self.column, self.message
+        )?;
+
+        if !self.text.is_empty() {
+            write!(f, ": {:?}", self.text)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A line of input along with its 1-based line number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a> {
+    /// 1-based line number.
+    pub number: usize,
+    /// The line, without its line ending.
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// Error for `token`, which should be a slice of this line so its column can be found.
+    /// Anything else is reported against the whole line.
+    pub fn error(&self, token: &str, message: impl Into<String>) -> ParseError {
+        let start = self.text.as_ptr() as usize;
+        let token_start = token.as_ptr() as usize;
+
+        if token_start >= start && token_start + token.len() <= start + self.text.len() {
+            let column = self.text[..token_start - start].chars().count() + 1;
+            ParseError::new(self.number, column, token, message)
+        } else {
+            ParseError::new(self.number, 1, self.text, message)
+        }
+    }
+
+    /// Splits the line on `sep`, failing unless there are exactly `N` tokens.
+    pub fn tokens<const N: usize>(&self, sep: &str) -> ParseResult<[&'a str; N]> {
+        self.split(self.text, sep)
+    }
+
+    /// Splits `text`, a slice of this line, on `sep`, failing unless there are exactly `N` tokens.
+    pub fn split<const N: usize>(&self, text: &'a str, sep: &str) -> ParseResult<[&'a str; N]> {
+        let tokens = text.split(sep).collect::<Vec<_>>();
+
+        tokens.try_into().map_err(|tokens: Vec<&str>| {
+            self.error(
+                text,
+                format!(
+                    "Expected {} tokens split by {:?}, found {}",
+                    N,
+                    sep,
+                    tokens.len()
+                ),
+            )
+        })
+    }
+
+    /// Parses `token`, a slice of this line, reporting it as an invalid `what` on failure.
+    pub fn parse<T: FromStr>(&self, token: &'a str, what: &str) -> ParseResult<T> {
+        token
+            .parse()
+            .map_err(|_| self.error(token, format!("Invalid {}", what)))
+    }
+
+    /// Runs a nom `parser` over `text`, a slice of this line, which it must consume entirely.
+    pub fn nom<T>(
+        &self,
+        text: &'a str,
+        mut parser: impl nom::Parser<&'a str, T, nom::error::Error<&'a str>>,
+        what: &str,
+    ) -> ParseResult<T> {
+        match parser.parse(text) {
+            Ok(("", value)) => Ok(value),
+            Ok((rest, _)) => Err(self.error(rest, format!("Unexpected text after {}", what))),
+            Err(nom::Err::Error(e) | nom::Err::Failure(e)) => {
+                Err(self.error(e.input, format!("Invalid {}", what)))
+            }
+            Err(nom::Err::Incomplete(_)) => Err(self.error(text, format!("Incomplete {}", what))),
+        }
+    }
+
+    /// Strips `prefix` from the start of the line.
+    pub fn strip_prefix(&self, prefix: &str) -> ParseResult<&'a str> {
+        self.text
+            .strip_prefix(prefix)
+            .ok_or_else(|| self.error(self.text, format!("Expected {:?}", prefix)))
+    }
+}
+
+/// Numbers each line of `input`.
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input.lines().enumerate().map(|(idx, text)| Line {
+        number: idx + 1,
+        text,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_line_errors() {
+        let line = lines("move 1 from 2 to 1\nmove x from 1 to 3")
+            .nth(1)
+            .unwrap();
+        let [_, count, ..] = line.tokens::<6>(" ").unwrap();
+
+        let error = line.parse::<usize>(count, "crate count").unwrap_err();
+        assert_eq!(error, ParseError::new(2, 6, "x", "Invalid crate count"));
+        assert_eq!(
+            error.with_file("day_5.txt").to_string(),
+            "day_5.txt:2:6: Invalid crate count: \"x\""
+        );
+
+        let error = line.tokens::<2>(" ").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.text, "move x from 1 to 3");
+        assert_eq!(error.message, "Expected 2 tokens split by \" \", found 6");
+
+        assert!(line.strip_prefix("move ").is_ok());
+        assert_eq!(line.strip_prefix("addx ").unwrap_err().column, 1);
+    }
+}
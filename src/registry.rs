@@ -0,0 +1,48 @@
+//! List of every implemented day.
+
+use anyhow::{Error, Result};
+
+use crate::solution::DynSolution;
+
+/// Every implemented day, in day order.
+pub const SOLUTIONS: &[&dyn DynSolution] = &[
+    &crate::day_1::Day1,
+    &crate::day_2::Day2,
+    &crate::day_3::Day3,
+    &crate::day_4::Day4,
+    &crate::day_5::Day5,
+    &crate::day_6::Day6,
+    &crate::day_7::Day7,
+    &crate::day_8::Day8,
+    &crate::day_9::Day9,
+    &crate::day_10::Day10,
+    &crate::day_11::Day11,
+    &crate::day_12::Day12,
+    &crate::day_13::Day13,
+    &crate::day_14::Day14,
+    &crate::day_15::Day15,
+    &crate::day_16::Day16,
+    &crate::day_18::Day18,
+];
+
+/// Looks up a day in [`SOLUTIONS`].
+pub fn find(day: u32) -> Option<&'static dyn DynSolution> {
+    SOLUTIONS
+        .iter()
+        .copied()
+        .find(|solution| solution.day() == day)
+}
+
+/// Every registered day when `selection` is `None`
+pub fn select(
+    selection: Option<impl Iterator<Item = u32>>,
+) -> Result<Vec<&'static dyn DynSolution>> {
+    match selection {
+        Some(days) => days
+            .map(|day| {
+                find(day).ok_or_else(|| Error::msg(format!("Day {} is not implemented", day)))
+            })
+            .collect(),
+        None => Ok(SOLUTIONS.to_vec()),
+    }
+}
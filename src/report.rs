@@ -0,0 +1,138 @@
+//! Machine readable run reports.
+
+use std::time::Duration;
+
+use anyhow::Error;
+use serde::Serialize;
+
+use crate::answer::Answer;
+use crate::input::InputSource;
+use crate::solution::{DayRun, DynSolution};
+
+/// One machine readable result per day and part, emitted as a JSON line by `--format json`
+#[derive(Debug, Serialize)]
+pub struct PartRecord {
+    /// Puzzle day.
+    pub day: u32,
+    /// Part number.
+    pub part: u8,
+    /// Input the day ran against.
+    pub input: String,
+    /// Answer, if the part succeeded.
+    pub answer: Option<Answer>,
+    /// Parse time in nanoseconds, if parsing succeeded.
+    pub parse_ns: Option<u64>,
+    /// Part time in nanoseconds, if the part ran.
+    pub part_ns: Option<u64>,
+    /// Why the day or part failed.
+    pub error: Option<String>,
+}
+
+fn nanos(duration: Duration) -> u64 {
+    duration.as_nanos().try_into().unwrap_or(u64::MAX)
+}
+
+impl PartRecord {
+    /// Records for every part of a finished run.
+    pub fn from_run(day_run: &DayRun) -> Vec<Self> {
+        day_run
+            .parts
+            .iter()
+            .map(|part_run| {
+                let (answer, error) = match &part_run.answer {
+                    Ok(answer) => (Some(answer.clone()), None),
+                    Err(e) => (None, Some(format!("{:#}", e))),
+                };
+
+                Self {
+                    day: day_run.day,
+                    part: part_run.part,
+                    input: day_run.input.clone(),
+                    answer,
+                    parse_ns: Some(nanos(day_run.parse)),
+                    part_ns: Some(nanos(part_run.elapsed)),
+                    error,
+                }
+            })
+            .collect()
+    }
+
+    /// Records for a day whose input couldn't be read or parsed, one per requested part
+    pub fn failed(
+        solution: &dyn DynSolution,
+        part: Option<u8>,
+        input: &InputSource,
+        error: &Error,
+    ) -> Vec<Self> {
+        let parts = match part {
+            Some(part) => vec![part],
+            None => (1..=solution.parts()).collect(),
+        };
+
+        parts
+            .into_iter()
+            .map(|part| Self {
+                day: solution.day(),
+                part,
+                input: input.name(&solution.input_path()),
+                answer: None,
+                parse_ns: None,
+                part_ns: None,
+                error: Some(format!("{:#}", error)),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solution::PartRun;
+
+    #[test]
+    fn test_part_records() -> anyhow::Result<()> {
+        let day_run = DayRun {
+            day: 10,
+            input: "input/day_10.txt".to_string(),
+            parse: Duration::from_micros(1500),
+            parts: vec![
+                PartRun {
+                    part: 1,
+                    answer: Ok(Answer::Int(13140)),
+                    elapsed: Duration::from_millis(2),
+                },
+                PartRun {
+                    part: 2,
+                    answer: Err(Error::msg("Bad display")),
+                    elapsed: Duration::from_millis(3),
+                },
+            ],
+        };
+
+        let records = PartRecord::from_run(&day_run)
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(
+            records[0],
+            r#"{"day":10,"part":1,"input":"input/day_10.txt","answer":{"kind":"int","value":13140},"parse_ns":1500000,"part_ns":2000000,"error":null}"#
+        );
+        assert_eq!(
+            records[1],
+            r#"{"day":10,"part":2,"input":"input/day_10.txt","answer":null,"parse_ns":1500000,"part_ns":3000000,"error":"Bad display"}"#
+        );
+
+        let failed = PartRecord::failed(
+            &crate::day_16::Day16,
+            None,
+            &InputSource::Stdin,
+            &Error::msg("Invalid valve"),
+        );
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].input, "-");
+        assert_eq!(failed[0].error.as_deref(), Some("Invalid valve"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,531 @@
+//! Generating the skeleton of a new day: its module, input stubs, fuzz target and
+//! registration.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+/// Module skeleton, with `{DAY}` standing in for the day number.
+const TEMPLATE: &str = r#"//! Day {DAY}: TODO.
+
+use anyhow::{bail, Result};
+
+use crate::answer::Answer;
+use crate::parse::{self, ParseResult};
+use crate::solution::Solution;
+
+/// [`Solution`] for day {DAY}.
+pub struct Day{DAY};
+
+impl Solution for Day{DAY} {
+    const DAY: u32 = {DAY};
+
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> ParseResult<Self::Input> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        day_{DAY}_1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        day_{DAY}_2(input)
+    }
+}
+
+/// TODO.
+pub fn day_{DAY}_1(input: &[String]) -> Result<Answer> {
+    bail!("Part 1 isn't solved yet, got {} lines", input.len())
+}
+
+/// TODO.
+pub fn day_{DAY}_2(input: &[String]) -> Result<Answer> {
+    bail!("Part 2 isn't solved yet, got {} lines", input.len())
+}
+
+/// Parses the puzzle input, one entry per line.
+pub fn parse(input: &str) -> ParseResult<Vec<String>> {
+    Ok(parse::lines(input)
+        .map(|line| line.text.to_string())
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let input = std::fs::read_to_string("input/day_{DAY}_test.txt")?;
+        parse(&input)?;
+
+        Ok(())
+    }
+}
+"#;
+
+/// Fuzz target skeleton, with `{DAY}` standing in for the day number.
+const FUZZ_TEMPLATE: &str = r#"#![no_main]
+
+use advent_of_code_2022::day_{DAY}::Day{DAY};
+use advent_of_code_2022::solution::Solution;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = Day{DAY}::parse(input);
+});
+"#;
+
+/// rustfmt's line width, which the regenerated bench imports wrap at.
+const MAX_WIDTH: usize = 100;
+
+/// Source of a new `day_N` module, following the layout every other day uses.
+pub fn module_source(day: u32) -> String {
+    TEMPLATE.replace("{DAY}", &day.to_string())
+}
+
+/// Source of the fuzz target for `day_N`'s parser.
+pub fn fuzz_target_source(day: u32) -> String {
+    FUZZ_TEMPLATE.replace("{DAY}", &day.to_string())
+}
+
+/// Adds `pub mod day_N;` to lib.rs, keeping the module list sorted.
+pub fn register_module(lib_rs: &str, day: u32) -> Result<String> {
+    let module = format!("day_{}", day);
+    let new_line = format!("pub mod {};", module);
+
+    let mut lines = lib_rs.lines().collect::<Vec<_>>();
+    let modules = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, line)| Some((idx, line.strip_prefix("pub mod ")?.strip_suffix(';')?)))
+        .collect::<Vec<_>>();
+    if modules.iter().any(|&(_, name)| name == module) {
+        bail!("lib.rs already declares {}", module);
+    }
+
+    let idx = match modules.iter().find(|&&(_, name)| name > module.as_str()) {
+        Some(&(idx, _)) => idx,
+        None => match modules.last() {
+            Some(&(idx, _)) => idx + 1,
+            None => bail!("No module declarations found in lib.rs"),
+        },
+    };
+    lines.insert(idx, &new_line);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Adds `DayN` to [`crate::registry::SOLUTIONS`], keeping the list in day order.
+pub fn register_solution(registry_rs: &str, day: u32) -> Result<String> {
+    let new_line = format!("    &crate::day_{0}::Day{0},", day);
+
+    let mut lines = registry_rs.lines().collect::<Vec<_>>();
+    let entries = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let (module, _) = line.trim().strip_prefix("&crate::day_")?.split_once("::")?;
+            Some((idx, module.parse::<u32>().ok()?))
+        })
+        .collect::<Vec<_>>();
+    if entries.iter().any(|&(_, registered)| registered == day) {
+        bail!("Day {} is already registered", day);
+    }
+
+    let idx = match entries.iter().find(|&&(_, registered)| registered > day) {
+        Some(&(idx, _)) => idx,
+        None => match entries.last() {
+            Some(&(idx, _)) => idx + 1,
+            None => bail!("No SOLUTIONS entries found in registry.rs"),
+        },
+    };
+    lines.insert(idx, &new_line);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Adds `day_N` to the bench's crate imports and a `bench_solution` call for it to
+/// `bench_days`, keeping the calls in day order.
+pub fn register_bench(days_rs: &str, day: u32) -> Result<String> {
+    let module = format!("day_{}", day);
+    let new_line = format!("    bench_solution::<day_{0}::Day{0}>(c);", day);
+
+    // Slow days are benched in their own group, so look through the whole file
+    let benched = format!("bench_solution::<day_{}::", day);
+    if days_rs
+        .lines()
+        .any(|line| line.trim().starts_with(&benched))
+    {
+        bail!("Day {} is already benchmarked", day);
+    }
+
+    let mut lines = days_rs.lines().map(str::to_string).collect::<Vec<_>>();
+    let Some(start) = lines
+        .iter()
+        .position(|line| line.starts_with("fn bench_days("))
+    else {
+        bail!("No bench_days function found in days.rs");
+    };
+    let entries = lines
+        .iter()
+        .enumerate()
+        .skip(start + 1)
+        .take_while(|(_, line)| *line != "}")
+        .filter_map(|(idx, line)| {
+            let (module, _) = line
+                .trim()
+                .strip_prefix("bench_solution::<day_")?
+                .split_once("::")?;
+            Some((idx, module.parse::<u32>().ok()?))
+        })
+        .collect::<Vec<_>>();
+    let idx = match entries.iter().find(|&&(_, benched)| benched > day) {
+        Some(&(idx, _)) => idx,
+        None => match entries.last() {
+            Some(&(idx, _)) => idx + 1,
+            None => bail!("No bench_solution calls found in bench_days"),
+        },
+    };
+    lines.insert(idx, new_line);
+
+    // The imports are one rustfmt'd `use`, so rebuild it rather than splicing a name in
+    const PREFIX: &str = "use advent_of_code_2022::{";
+    let Some(use_start) = lines.iter().position(|line| line.starts_with(PREFIX)) else {
+        bail!("No advent_of_code_2022 imports found in days.rs");
+    };
+    let Some(use_end) = (use_start..lines.len()).find(|&idx| lines[idx].ends_with("};")) else {
+        bail!("Unterminated advent_of_code_2022 imports in days.rs");
+    };
+    let imports = lines[use_start..=use_end].join(" ");
+    let imports = &imports[PREFIX.len()..imports.len() - "};".len()];
+    let mut names = imports
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<_>>();
+    if !names.contains(&module.as_str()) {
+        names.push(&module);
+    }
+    names.sort_unstable();
+    let imports = format_imports(PREFIX, &names);
+    lines.splice(use_start..=use_end, imports);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// `prefix` followed by `names` and `};`, wrapped the way rustfmt would.
+fn format_imports(prefix: &str, names: &[&str]) -> Vec<String> {
+    let single = format!("{}{}}};", prefix, names.join(", "));
+    if single.len() <= MAX_WIDTH {
+        return vec![single];
+    }
+
+    let mut lines = vec![prefix.to_string()];
+    let mut line = String::new();
+    for name in names {
+        if !line.is_empty() && line.len() + 1 + name.len() + 1 > MAX_WIDTH {
+            lines.push(std::mem::take(&mut line));
+        }
+        if line.is_empty() {
+            line.push_str("   ");
+        }
+        line.push(' ');
+        line.push_str(name);
+        line.push(',');
+    }
+    lines.push(line);
+    lines.push("};".to_string());
+
+    lines
+}
+
+/// Adds a `[[bin]]` for `day_N`'s fuzz target to the fuzz crate's manifest, keeping the
+/// targets in day order.
+pub fn register_fuzz_target(cargo_toml: &str, day: u32) -> Result<String> {
+    let block = [
+        "[[bin]]".to_string(),
+        format!("name = \"day_{}\"", day),
+        format!("path = \"fuzz_targets/day_{}.rs\"", day),
+        "test = false".to_string(),
+        "doc = false".to_string(),
+        "bench = false".to_string(),
+        String::new(),
+    ];
+
+    let mut lines = cargo_toml.lines().map(str::to_string).collect::<Vec<_>>();
+    let targets = lines
+        .windows(2)
+        .enumerate()
+        .filter(|(_, pair)| pair[0] == "[[bin]]")
+        .filter_map(|(idx, pair)| {
+            let name = pair[1].strip_prefix("name = \"day_")?.strip_suffix('"')?;
+            Some((idx, name.parse::<u32>().ok()?))
+        })
+        .collect::<Vec<_>>();
+    if targets.iter().any(|&(_, target)| target == day) {
+        bail!("Day {} already has a fuzz target", day);
+    }
+
+    match targets.iter().find(|&&(_, target)| target > day) {
+        Some(&(idx, _)) => {
+            lines.splice(idx..idx, block);
+        }
+        None => {
+            if lines.last().is_some_and(|line| !line.is_empty()) {
+                lines.push(String::new());
+            }
+            lines.extend(block);
+            lines.pop();
+        }
+    }
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Writes every file next to its destination first and only then renames them into place, so
+/// a failed write leaves none of them changed.
+fn write_all(files: &[(PathBuf, String)]) -> Result<()> {
+    let mut staged = Vec::new();
+    for (path, contents) in files {
+        let mut staged_name = path.file_name().unwrap_or_default().to_os_string();
+        staged_name.push(".new-day");
+        let staged_path = path.with_file_name(staged_name);
+
+        if let Err(e) = std::fs::write(&staged_path, contents) {
+            for staged_path in staged {
+                let _ = std::fs::remove_file(staged_path);
+            }
+            return Err(e).with_context(|| format!("Failed to write {}", staged_path.display()));
+        }
+        staged.push(staged_path);
+    }
+
+    for (staged_path, (path, _)) in staged.iter().zip(files) {
+        std::fs::rename(staged_path, path)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+/// Creates day `day` under the crate at `root`: writes `src/day_N.rs` and its fuzz target,
+/// creates empty `input/day_N.txt` and `input/day_N_test.txt` stubs if missing, and registers
+/// the day with the library, the runner, the benches and the fuzz crate. Nothing is written if
+/// the day already exists or any registration fails. Returns every file written.
+pub fn new_day(root: impl AsRef<Path>, day: u32) -> Result<Vec<PathBuf>> {
+    let root = root.as_ref();
+    let module_path = root.join("src").join(format!("day_{}.rs", day));
+    let fuzz_target_path = root.join(format!("fuzz/fuzz_targets/day_{}.rs", day));
+    let lib_path = root.join("src/lib.rs");
+    let registry_path = root.join("src/registry.rs");
+    let bench_path = root.join("benches/days.rs");
+    let fuzz_manifest_path = root.join("fuzz/Cargo.toml");
+
+    for path in [&module_path, &fuzz_target_path] {
+        if path.exists() {
+            bail!("{} already exists", path.display());
+        }
+    }
+    let read = |path: &Path| {
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+    };
+    let mut files = vec![
+        (module_path, module_source(day)),
+        (fuzz_target_path, fuzz_target_source(day)),
+        (lib_path.clone(), register_module(&read(&lib_path)?, day)?),
+        (
+            registry_path.clone(),
+            register_solution(&read(&registry_path)?, day)?,
+        ),
+        (
+            bench_path.clone(),
+            register_bench(&read(&bench_path)?, day)?,
+        ),
+        (
+            fuzz_manifest_path.clone(),
+            register_fuzz_target(&read(&fuzz_manifest_path)?, day)?,
+        ),
+    ];
+
+    let input_dir = root.join(crate::verify::INPUT_DIR);
+    for name in [format!("day_{}.txt", day), format!("day_{}_test.txt", day)] {
+        let path = input_dir.join(name);
+        if !path.exists() {
+            files.push((path, String::new()));
+        }
+    }
+
+    for dir in [input_dir, root.join("fuzz/fuzz_targets")] {
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    write_all(&files)?;
+
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const LIB_RS: &str = "//! Docs.\n\npub mod answer;\npub mod day_1;\npub mod day_10;\n\
+                          pub mod day_2;\npub mod parse;\n";
+    const REGISTRY_RS: &str = "pub const SOLUTIONS: &[&dyn DynSolution] = &[\n    \
+                               &crate::day_1::Day1,\n    &crate::day_2::Day2,\n    \
+                               &crate::day_10::Day10,\n];\n";
+    const DAYS_RS: &str = "use advent_of_code_2022::{day_1, day_10, day_16, day_18, day_2};\n\n\
+                           fn bench_days(c: &mut Criterion) {\n    \
+                           bench_solution::<day_1::Day1>(c);\n    \
+                           bench_solution::<day_2::Day2>(c);\n    \
+                           bench_solution::<day_10::Day10>(c);\n    \
+                           bench_solution::<day_18::Day18>(c);\n}\n\n\
+                           fn bench_slow_days(c: &mut Criterion) {\n    \
+                           bench_solution::<day_16::Day16>(c);\n}\n";
+    const FUZZ_TOML: &str = "[package]\nname = \"advent-of-code-2022-fuzz\"\n\n\
+                             [[bin]]\nname = \"day_1\"\npath = \"fuzz_targets/day_1.rs\"\n\
+                             test = false\ndoc = false\nbench = false\n\n\
+                             [[bin]]\nname = \"day_16\"\npath = \"fuzz_targets/day_16.rs\"\n\
+                             test = false\ndoc = false\nbench = false\n\n\
+                             [[bin]]\nname = \"day_18\"\npath = \"fuzz_targets/day_18.rs\"\n\
+                             test = false\ndoc = false\nbench = false\n";
+
+    #[test]
+    fn test_register() -> Result<()> {
+        assert_eq!(
+            register_module(LIB_RS, 17)?,
+            "//! Docs.\n\npub mod answer;\npub mod day_1;\npub mod day_10;\npub mod day_17;\n\
+             pub mod day_2;\npub mod parse;\n"
+        );
+        assert!(register_module(LIB_RS, 10).is_err());
+
+        assert_eq!(
+            register_solution(REGISTRY_RS, 3)?,
+            "pub const SOLUTIONS: &[&dyn DynSolution] = &[\n    &crate::day_1::Day1,\n    \
+             &crate::day_2::Day2,\n    &crate::day_3::Day3,\n    &crate::day_10::Day10,\n];\n"
+        );
+        assert!(
+            register_solution(REGISTRY_RS, 17)?.contains("Day10,\n    &crate::day_17::Day17,\n];")
+        );
+        assert!(register_solution(REGISTRY_RS, 2).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_register_bench() -> Result<()> {
+        let registered = register_bench(DAYS_RS, 17)?;
+
+        assert!(registered.starts_with(
+            "use advent_of_code_2022::{day_1, day_10, day_16, day_17, day_18, day_2};\n"
+        ));
+        assert!(registered.contains(
+            "Day10>(c);\n    bench_solution::<day_17::Day17>(c);\n    \
+             bench_solution::<day_18::Day18>(c);"
+        ));
+        assert!(register_bench(DAYS_RS, 16).is_err());
+
+        let registered = register_bench(&registered, 19)?;
+        assert!(registered.contains("Day18>(c);\n    bench_solution::<day_19::Day19>(c);\n}"));
+        assert!(register_bench("fn bench_days(c: &mut Criterion) {\n}\n", 1).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_imports() {
+        let mut names = (1..=18)
+            .map(|day| format!("day_{}", day))
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        let names = names.iter().map(String::as_str).collect::<Vec<_>>();
+
+        assert_eq!(
+            format_imports("use advent_of_code_2022::{", &names).join("\n"),
+            "use advent_of_code_2022::{\n    day_1, day_10, day_11, day_12, day_13, day_14, day_15, \
+             day_16, day_17, day_18, day_2, day_3,\n    day_4, day_5, day_6, day_7, day_8, day_9,\n};"
+        );
+        assert_eq!(
+            format_imports("use advent_of_code_2022::{", &["day_1", "day_2"]),
+            vec!["use advent_of_code_2022::{day_1, day_2};"]
+        );
+    }
+
+    #[test]
+    fn test_register_fuzz_target() -> Result<()> {
+        let registered = register_fuzz_target(FUZZ_TOML, 17)?;
+
+        assert!(registered.contains(
+            "bench = false\n\n[[bin]]\nname = \"day_17\"\npath = \"fuzz_targets/day_17.rs\"\n\
+             test = false\ndoc = false\nbench = false\n\n[[bin]]\nname = \"day_18\""
+        ));
+        assert!(register_fuzz_target(FUZZ_TOML, 16).is_err());
+
+        let registered = register_fuzz_target(&registered, 19)?;
+        assert!(registered.ends_with(
+            "bench = false\n\n[[bin]]\nname = \"day_19\"\npath = \"fuzz_targets/day_19.rs\"\n\
+             test = false\ndoc = false\nbench = false\n"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_day() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        for dir in ["src", "benches", "fuzz"] {
+            std::fs::create_dir(root.path().join(dir))?;
+        }
+        std::fs::write(root.path().join("src/lib.rs"), LIB_RS)?;
+        std::fs::write(root.path().join("src/registry.rs"), REGISTRY_RS)?;
+        std::fs::create_dir(root.path().join("input"))?;
+        std::fs::write(root.path().join("input/day_17.txt"), "downloaded")?;
+
+        // Without a bench file to register in, nothing is written at all
+        assert!(new_day(root.path(), 17).is_err());
+        assert!(!root.path().join("src/day_17.rs").exists());
+        assert_eq!(
+            std::fs::read_to_string(root.path().join("src/lib.rs"))?,
+            LIB_RS
+        );
+
+        std::fs::write(root.path().join("benches/days.rs"), DAYS_RS)?;
+        std::fs::write(root.path().join("fuzz/Cargo.toml"), FUZZ_TOML)?;
+        let written = new_day(root.path(), 17)?;
+        assert_eq!(written.len(), 7);
+        assert!(
+            std::fs::read_dir(root.path().join("src"))?.all(|entry| !entry
+                .unwrap()
+                .file_name()
+                .to_string_lossy()
+                .ends_with(".new-day"))
+        );
+
+        let fuzz_target = std::fs::read_to_string(root.path().join("fuzz/fuzz_targets/day_17.rs"))?;
+        assert!(fuzz_target.contains("let _ = Day17::parse(input);"));
+        assert!(
+            std::fs::read_to_string(root.path().join("benches/days.rs"))?
+                .contains("bench_solution::<day_17::Day17>(c);")
+        );
+        assert!(
+            std::fs::read_to_string(root.path().join("fuzz/Cargo.toml"))?
+                .contains("name = \"day_17\"")
+        );
+
+        let module = std::fs::read_to_string(root.path().join("src/day_17.rs"))?;
+        assert!(module.contains("pub struct Day17;"));
+        assert!(module.contains("pub fn day_17_2(input: &[String]) -> Result<Answer>"));
+        assert!(module.contains("\"input/day_17_test.txt\""));
+        assert_eq!(
+            std::fs::read_to_string(root.path().join("input/day_17.txt"))?,
+            "downloaded"
+        );
+        assert!(root.path().join("input/day_17_test.txt").exists());
+
+        assert!(new_day(root.path(), 17).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+//! The [`Solution`] trait every day implements and the runner built on it.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use tracing::info_span;
+
+use crate::answer::Answer;
+use crate::input::InputSource;
+use crate::parse::ParseResult;
+
+/// A day's puzzle: how to parse its input and solve each part.
+pub trait Solution {
+    /// Puzzle day, 1 to 25.
+    const DAY: u32;
+    /// Number of parts solved so far.
+    const PARTS: u8 = 2;
+
+    /// Parsed puzzle model shared by both parts.
+    type Input;
+
+    /// Input used when none is given, `input/day_N.txt` by default.
+    fn input_path() -> String {
+        format!("input/day_{}.txt", Self::DAY)
+    }
+
+    /// Parses the raw puzzle input.
+    fn parse(input: &str) -> ParseResult<Self::Input>;
+
+    /// Solves part 1.
+    fn part1(input: &Self::Input) -> Result<Answer>;
+
+    /// Solves part 2. Days without a part 2 leave `PARTS` at 1 and keep this default.
+    fn part2(_input: &Self::Input) -> Result<Answer> {
+        bail!("Day {} part 2 is not implemented", Self::DAY)
+    }
+}
+
+/// A failing part doesn't stop the others, so each part carries its own result
+#[derive(Debug)]
+pub struct PartRun {
+    /// Part number, 1 or 2.
+    pub part: u8,
+    /// The part's answer, or why it failed.
+    pub answer: Result<Answer>,
+    /// Time spent solving the part.
+    pub elapsed: Duration,
+}
+
+/// Result of running a day: its parse time and every part that was run.
+#[derive(Debug)]
+pub struct DayRun {
+    /// Puzzle day.
+    pub day: u32,
+    /// Name of the input the day ran against.
+    pub input: String,
+    /// Time spent in [`Solution::parse`].
+    pub parse: Duration,
+    /// Parts in order.
+    pub parts: Vec<PartRun>,
+}
+
+impl DayRun {
+    /// Parse time plus every part's time.
+    pub fn total(&self) -> Duration {
+        self.parse + self.parts.iter().map(|part| part.elapsed).sum::<Duration>()
+    }
+}
+
+/// Object safe view of a `Solution` so the registry can hold every day in one list
+pub trait DynSolution: Sync {
+    /// Puzzle day.
+    fn day(&self) -> u32;
+
+    /// Number of parts solved so far.
+    fn parts(&self) -> u8;
+
+    /// Input used when none is given.
+    fn input_path(&self) -> String;
+
+    /// Reads and parses `input`, then runs `part` or every part when `None`.
+    ///
+    /// Fails if the input can't be read or parsed. Part failures are reported in the
+    /// returned [`PartRun`]s instead.
+    fn run(&self, part: Option<u8>, input: &InputSource) -> Result<DayRun>;
+}
+
+impl<S: Solution + Sync> DynSolution for S {
+    fn day(&self) -> u32 {
+        S::DAY
+    }
+
+    fn parts(&self) -> u8 {
+        S::PARTS
+    }
+
+    fn input_path(&self) -> String {
+        S::input_path()
+    }
+
+    fn run(&self, part: Option<u8>, input: &InputSource) -> Result<DayRun> {
+        if let Some(part) = part {
+            if part == 0 || part > S::PARTS {
+                bail!("Day {} has no part {}", S::DAY, part);
+            }
+        }
+
+        let input_name = input.name(&S::input_path());
+        let _day = info_span!("day", day = S::DAY, input = %input_name).entered();
+        let input = input.read(&S::input_path())?;
+
+        let start = Instant::now();
+        let input = info_span!("parse")
+            .in_scope(|| S::parse(&input))
+            .map_err(|e| e.with_file(&input_name))?;
+        let parse = start.elapsed();
+
+        let mut parts = Vec::new();
+        for cur_part in 1..=S::PARTS {
+            if part.is_some() && part != Some(cur_part) {
+                continue;
+            }
+
+            let _part = info_span!("part", part = cur_part).entered();
+            let start = Instant::now();
+            let answer = match cur_part {
+                1 => S::part1(&input),
+                _ => S::part2(&input),
+            };
+
+            parts.push(PartRun {
+                part: cur_part,
+                answer,
+                elapsed: start.elapsed(),
+            });
+        }
+
+        Ok(DayRun {
+            day: S::DAY,
+            input: input_name,
+            parse,
+            parts,
+        })
+    }
+}
@@ -0,0 +1,338 @@
+//! Submitting answers, and the local log of every submission made.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::http::HttpClient;
+
+/// Local, untracked log of every submission, one JSON object per line.
+pub const LOG_PATH: &str = "submissions.jsonl";
+
+/// How the site judged a submission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    /// The answer was accepted.
+    Correct,
+    /// The answer was wrong.
+    Incorrect,
+    /// Submitted too soon after a wrong answer. The answer wasn't judged.
+    TooSoon,
+    /// The part is already solved, or its earlier part isn't yet.
+    WrongLevel,
+    /// The reply wasn't recognised.
+    Unknown,
+}
+
+/// The site's reply to a submission.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reply {
+    /// How the answer was judged.
+    pub verdict: Verdict,
+    /// How long to wait before submitting again, if the site asked for a wait.
+    pub wait: Option<Duration>,
+    /// The reply's text with markup stripped.
+    pub message: String,
+}
+
+impl Reply {
+    /// Reads the verdict and any requested wait out of the reply page.
+    pub fn parse(body: &str) -> Self {
+        let article = body
+            .split_once("<article>")
+            .and_then(|(_, rest)| rest.split_once("</article>"))
+            .map_or(body, |(article, _)| article);
+        let message = strip_tags(article);
+
+        let verdict = if message.contains("That's the right answer") {
+            Verdict::Correct
+        } else if message.contains("That's not the right answer") {
+            Verdict::Incorrect
+        } else if message.contains("You gave an answer too recently") {
+            Verdict::TooSoon
+        } else if message.contains("You don't seem to be solving the right level") {
+            Verdict::WrongLevel
+        } else {
+            Verdict::Unknown
+        };
+
+        Self {
+            verdict,
+            wait: parse_wait(&message),
+            message,
+        }
+    }
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Finds "You have 1m 5s left to wait" or "please wait 5 minutes before trying again".
+fn parse_wait(message: &str) -> Option<Duration> {
+    if let Some((before, _)) = message.split_once(" left to wait") {
+        let (_, wait) = before.rsplit_once("You have ")?;
+        return humantime::parse_duration(wait).ok();
+    }
+
+    let (_, after) = message.split_once("wait ")?;
+    let (count, _) = after.split_once(" minute")?;
+    let minutes = match count {
+        "one" => 1,
+        count => count.parse().ok()?,
+    };
+
+    Some(Duration::from_secs(minutes * 60))
+}
+
+/// A logged submission.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Submission {
+    /// When the answer was sent, as RFC 3339.
+    pub timestamp: String,
+    /// Puzzle day.
+    pub day: u32,
+    /// Part, 1 or 2.
+    pub part: u8,
+    /// The submitted answer.
+    pub answer: String,
+    /// How the site judged it.
+    pub verdict: Verdict,
+    /// Seconds to wait before submitting this part again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wait: Option<u64>,
+    /// The site's reply text.
+    pub response: String,
+}
+
+impl Submission {
+    fn sent_at(&self) -> Result<SystemTime> {
+        humantime::parse_rfc3339(&self.timestamp)
+            .with_context(|| format!("Invalid timestamp {:?}", self.timestamp))
+    }
+
+    fn is_for(&self, day: u32, part: u8) -> bool {
+        self.day == day && self.part == part
+    }
+}
+
+/// Every submission made so far, backed by an append-only file.
+#[derive(Debug)]
+pub struct SubmissionLog {
+    path: PathBuf,
+    entries: Vec<Submission>,
+}
+
+impl SubmissionLog {
+    /// Loads the log, treating a missing file as empty.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if !path.exists() {
+            return Ok(Self {
+                path,
+                entries: Vec::new(),
+            });
+        }
+
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let entries = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| {
+                serde_json::from_str(line)
+                    .with_context(|| format!("{}:{}: invalid submission", path.display(), idx + 1))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { path, entries })
+    }
+
+    /// Every submission, oldest first.
+    pub fn entries(&self) -> &[Submission] {
+        &self.entries
+    }
+
+    /// Fails if `answer` shouldn't be sent for a day and part at `now`: the part is already
+    /// solved, the answer was already rejected, or the site asked to wait.
+    pub fn check(&self, day: u32, part: u8, answer: &str, now: SystemTime) -> Result<()> {
+        let mut entries = self.entries.iter().filter(|entry| entry.is_for(day, part));
+
+        for entry in entries.clone() {
+            match entry.verdict {
+                Verdict::Correct => bail!(
+                    "Day {} part {} was already solved with {}",
+                    day,
+                    part,
+                    entry.answer
+                ),
+                Verdict::Incorrect if entry.answer == answer => bail!(
+                    "{} was already rejected for day {} part {} at {}",
+                    answer,
+                    day,
+                    part,
+                    entry.timestamp
+                ),
+                _ => {}
+            }
+        }
+
+        if let Some(entry) = entries.next_back() {
+            if let Some(wait) = entry.wait {
+                let retry_at = entry.sent_at()? + Duration::from_secs(wait);
+                if let Ok(remaining) = retry_at.duration_since(now) {
+                    // Round up so the suggested wait is never too short
+                    let remaining = Duration::from_secs(remaining.as_secs_f64().ceil() as u64);
+                    if remaining.is_zero() {
+                        return Ok(());
+                    }
+                    bail!(
+                        "The site asked to wait, try day {} part {} again in {}",
+                        day,
+                        part,
+                        humantime::format_duration(remaining)
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds a submission to the log and the file behind it.
+    pub fn append(&mut self, submission: Submission) -> Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open {}", self.path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(&submission)?)
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+
+        self.entries.push(submission);
+
+        Ok(())
+    }
+}
+
+/// Sends `answer` for a day and part unless the log says not to, then logs the reply.
+pub fn submit(
+    client: &dyn HttpClient,
+    config: &Config,
+    log: &mut SubmissionLog,
+    day: u32,
+    part: u8,
+    answer: &str,
+) -> Result<Submission> {
+    let now = SystemTime::now();
+    log.check(day, part, answer, now)?;
+
+    let url = config.answer_url(day);
+    let level = part.to_string();
+    let response = client.post_form(&url, &[("level", &level), ("answer", answer)])?;
+    if !response.is_success() {
+        bail!(
+            "Submitting to {} failed with status {}: {}",
+            url,
+            response.status,
+            strip_tags(&response.body)
+        );
+    }
+
+    let reply = Reply::parse(&response.body);
+    let submission = Submission {
+        timestamp: humantime::format_rfc3339_seconds(now).to_string(),
+        day,
+        part,
+        answer: answer.to_string(),
+        verdict: reply.verdict,
+        wait: reply.wait.map(|wait| wait.as_secs()),
+        response: reply.message,
+    };
+    log.append(submission.clone())?;
+
+    Ok(submission)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_reply() {
+        let reply = Reply::parse(
+            "<main>\n<article><p>That's not the right answer; your answer is too low.  Please \
+             wait one minute before trying again. [<a href=\"/2022/day/1\">Return to Day 1</a>]\
+             </p></article>\n</main>",
+        );
+        assert_eq!(reply.verdict, Verdict::Incorrect);
+        assert_eq!(reply.wait, Some(Duration::from_secs(60)));
+        assert!(reply
+            .message
+            .starts_with("That's not the right answer; your answer is too low."));
+
+        let reply = Reply::parse(
+            "<article><p>You gave an answer too recently; you have to wait after submitting an \
+             answer before trying again.  You have 1m 5s left to wait.</p></article>",
+        );
+        assert_eq!(reply.verdict, Verdict::TooSoon);
+        assert_eq!(reply.wait, Some(Duration::from_secs(65)));
+
+        let reply = Reply::parse("<article><p>That's the right answer!</p></article>");
+        assert_eq!(reply.verdict, Verdict::Correct);
+        assert_eq!(reply.wait, None);
+    }
+
+    #[test]
+    fn test_check() -> Result<()> {
+        let sent = humantime::parse_rfc3339("2022-12-05T06:00:00Z")?;
+        let submission = |part, answer: &str, verdict, wait| Submission {
+            timestamp: humantime::format_rfc3339_seconds(sent).to_string(),
+            day: 5,
+            part,
+            answer: answer.to_string(),
+            verdict,
+            wait,
+            response: String::new(),
+        };
+        let log = SubmissionLog {
+            path: PathBuf::new(),
+            entries: vec![
+                submission(1, "CMZ", Verdict::Correct, None),
+                submission(2, "ABC", Verdict::Incorrect, Some(300)),
+            ],
+        };
+
+        assert!(log.check(5, 1, "XYZ", sent).is_err());
+        assert!(log
+            .check(5, 2, "ABC", sent + Duration::from_secs(600))
+            .is_err());
+        assert!(log
+            .check(5, 2, "MCD", sent + Duration::from_secs(60))
+            .is_err());
+        assert!(log
+            .check(5, 2, "MCD", sent + Duration::from_secs(300))
+            .is_ok());
+        assert!(log.check(6, 1, "7", sent).is_ok());
+
+        Ok(())
+    }
+}
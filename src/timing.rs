@@ -0,0 +1,89 @@
+//! Timing reports for solver runs.
+
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::solution::DayRun;
+
+/// Formats a duration for reports, e.g. `18.01ms`.
+pub fn format_duration(duration: Duration) -> String {
+    format!("{:.2?}", duration)
+}
+
+/// Table of every day's parse and part times, most expensive day first
+pub fn summary_table(runs: &[DayRun]) -> String {
+    let mut runs = runs.iter().collect::<Vec<_>>();
+    runs.sort_by_key(|run| std::cmp::Reverse(run.total()));
+
+    let part_time = |run: &DayRun, part: u8| {
+        run.parts
+            .iter()
+            .find(|part_run| part_run.part == part)
+            .map(|part_run| format_duration(part_run.elapsed))
+            .unwrap_or_else(|| "-".to_string())
+    };
+
+    let mut table = String::new();
+    let _ = writeln!(
+        table,
+        "{:>4}  {:>12}  {:>12}  {:>12}  {:>12}",
+        "Day", "Parse", "Part 1", "Part 2", "Total"
+    );
+    for run in runs {
+        let _ = writeln!(
+            table,
+            "{:>4}  {:>12}  {:>12}  {:>12}  {:>12}",
+            run.day,
+            format_duration(run.parse),
+            part_time(run, 1),
+            part_time(run, 2),
+            format_duration(run.total())
+        );
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::answer::Answer;
+    use crate::solution::PartRun;
+
+    fn day_run(day: u32, parse: u64, parts: &[u64]) -> DayRun {
+        DayRun {
+            day,
+            input: format!("input/day_{}.txt", day),
+            parse: Duration::from_millis(parse),
+            parts: parts
+                .iter()
+                .enumerate()
+                .map(|(idx, elapsed)| PartRun {
+                    part: idx as u8 + 1,
+                    answer: Ok(Answer::Int(0)),
+                    elapsed: Duration::from_millis(*elapsed),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_summary_table() {
+        let runs = [
+            day_run(1, 1, &[1, 1]),
+            day_run(11, 2, &[5, 300]),
+            day_run(16, 3, &[40]),
+        ];
+
+        let table = summary_table(&runs);
+        let lines = table.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].contains("Parse"));
+        assert!(lines[1].trim_start().starts_with("11"));
+        assert!(lines[1].ends_with("307.00ms"));
+        assert!(lines[2].trim_start().starts_with("16"));
+        assert!(lines[2].contains(" - "));
+        assert!(lines[3].trim_start().starts_with('1'));
+    }
+}
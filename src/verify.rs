@@ -0,0 +1,360 @@
+//! Accepted answers in `answers.toml` and checking solvers against them.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use toml_edit::{value, DocumentMut, Item, Table, Value};
+
+use crate::answer::Answer;
+use crate::input::InputSource;
+use crate::solution::{DynSolution, PartRun};
+
+/// Checked in file holding accepted answers.
+pub const ANSWERS_PATH: &str = "answers.toml";
+/// Directory recorded input names are relative to.
+pub const INPUT_DIR: &str = "input";
+
+/// Accepted answers, laid out as `[day_N."input_name"]` tables holding `part_1`/`part_2` strings.
+/// Edited through toml_edit so hand written comments survive `aoc record`.
+#[derive(Debug, Default)]
+pub struct AnswerFile {
+    doc: DocumentMut,
+}
+
+impl AnswerFile {
+    /// Loads an answers file, treating a missing file as empty.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+
+        contents.parse()
+    }
+
+    /// Writes the answers file back out.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+
+        std::fs::write(path, self.doc.to_string())
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    fn day_key(day: u32) -> String {
+        format!("day_{}", day)
+    }
+
+    fn part_key(part: u8) -> String {
+        format!("part_{}", part)
+    }
+
+    /// Names of every input with recorded answers for `day`.
+    pub fn inputs(&self, day: u32) -> Vec<String> {
+        self.doc
+            .get(&Self::day_key(day))
+            .and_then(Item::as_table_like)
+            .map(|inputs| inputs.iter().map(|(name, _)| name.to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Accepted answer for a day, input and part.
+    pub fn expected(&self, day: u32, input: &str, part: u8) -> Option<&str> {
+        self.doc
+            .get(&Self::day_key(day))?
+            .get(input)?
+            .get(Self::part_key(part))?
+            .as_str()
+    }
+
+    /// Records `answer` as accepted, replacing any previous answer.
+    pub fn record(&mut self, day: u32, input: &str, part: u8, answer: &Answer) -> Result<()> {
+        let day_table = self
+            .doc
+            .entry(&Self::day_key(day))
+            .or_insert_with(|| {
+                let mut table = Table::new();
+                table.set_implicit(true);
+                Item::Table(table)
+            })
+            .as_table_mut()
+            .with_context(|| format!("{} is not a table", Self::day_key(day)))?;
+
+        let input_table = day_table
+            .entry(input)
+            .or_insert_with(|| Item::Table(Table::new()))
+            .as_table_like_mut()
+            .with_context(|| format!("{}.{} is not a table", Self::day_key(day), input))?;
+
+        input_table.insert(&Self::part_key(part), answer_item(answer)?);
+
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for AnswerFile {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let doc = s.parse::<DocumentMut>().context("Invalid answers file")?;
+
+        Ok(Self { doc })
+    }
+}
+
+fn answer_item(answer: &Answer) -> Result<Item> {
+    let answer_str = answer.to_string();
+
+    match answer {
+        // Keep renders readable in the file rather than a single line full of `\n` escapes
+        Answer::Render(render) if !render.contains("'''") => {
+            let literal = format!("'''\n{}'''", render).parse::<Value>()?;
+            Ok(Item::Value(literal))
+        }
+        _ => Ok(value(answer_str)),
+    }
+}
+
+/// Name an input is recorded under: its path relative to `input/`
+pub fn input_name(solution: &dyn DynSolution, input: &InputSource) -> Result<String> {
+    let path = match input {
+        InputSource::Default => PathBuf::from(solution.input_path()),
+        InputSource::Path(path) => path.clone(),
+        InputSource::Stdin => bail!("Answers can't be recorded for stdin input"),
+    };
+
+    match path.strip_prefix(INPUT_DIR) {
+        Ok(name) => Ok(name.to_string_lossy().into_owned()),
+        Err(_) => bail!(
+            "Recorded inputs must live in {}/: {}",
+            INPUT_DIR,
+            path.display()
+        ),
+    }
+}
+
+/// How a part fared against its recorded answer.
+#[derive(Debug)]
+pub enum PartCheck {
+    /// Matched the recorded answer.
+    Passed,
+    /// Didn't match the recorded answer.
+    Mismatch {
+        /// The recorded answer.
+        expected: String,
+        /// What the part returned.
+        got: String,
+    },
+    /// The part returned an error.
+    Failed(anyhow::Error),
+    /// No answer is recorded. Holds what the part returned.
+    Unrecorded(String),
+}
+
+impl std::fmt::Display for PartCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PartCheck::Passed => write!(f, "ok"),
+            PartCheck::Mismatch { expected, got } => write!(
+                f,
+                "MISMATCH\n  expected: {}\n  got:      {}",
+                expected.trim_end().replace('\n', "\n            "),
+                got.trim_end().replace('\n', "\n            ")
+            ),
+            PartCheck::Failed(e) => write!(f, "FAILED {:?}", e),
+            PartCheck::Unrecorded(got) => write!(f, "not recorded, got {}", got),
+        }
+    }
+}
+
+/// Runs every part of `solution` against `input/<input>` and checks each against answers.toml.
+///
+/// Fails if the input can't be read or parsed.
+pub fn check_input(
+    solution: &dyn DynSolution,
+    answers: &AnswerFile,
+    input: &str,
+) -> Result<Vec<(u8, PartCheck)>> {
+    let day = solution.day();
+    let source = InputSource::Path(Path::new(INPUT_DIR).join(input));
+    let day_run = solution.run(None, &source)?;
+
+    let checks = day_run
+        .parts
+        .into_iter()
+        .map(|PartRun { part, answer, .. }| {
+            let check = match answer {
+                Ok(answer) => {
+                    let got = answer.to_string();
+                    match answers.expected(day, input, part) {
+                        Some(expected) if expected == got => PartCheck::Passed,
+                        Some(expected) => PartCheck::Mismatch {
+                            expected: expected.to_string(),
+                            got,
+                        },
+                        None => PartCheck::Unrecorded(got),
+                    }
+                }
+                Err(e) => PartCheck::Failed(e),
+            };
+
+            (part, check)
+        })
+        .collect();
+
+    Ok(checks)
+}
+
+/// What a [`verify`] run found for one day and recorded input.
+#[derive(Debug)]
+pub enum DayCheck {
+    /// The day has no recorded answers.
+    Unrecorded(u32),
+    /// A recorded input's parts, or why the input couldn't be run.
+    Input {
+        /// Puzzle day.
+        day: u32,
+        /// Input name, relative to `input/`.
+        input: String,
+        /// Each part's check.
+        checks: Result<Vec<(u8, PartCheck)>>,
+    },
+}
+
+/// Counts from a [`verify`] run.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct VerifySummary {
+    /// Parts matching their recorded answer.
+    pub passed: usize,
+    /// Parts that failed or didn't match.
+    pub failed: usize,
+    /// Days and parts without a recorded answer.
+    pub unrecorded: usize,
+}
+
+impl VerifySummary {
+    /// Tallies the checks from a [`verify`] run. An input that couldn't run counts as one
+    /// failure.
+    pub fn new(day_checks: &[DayCheck]) -> Self {
+        let mut summary = Self::default();
+
+        for day_check in day_checks {
+            match day_check {
+                DayCheck::Unrecorded(_) => summary.unrecorded += 1,
+                DayCheck::Input { checks: Err(_), .. } => summary.failed += 1,
+                DayCheck::Input {
+                    checks: Ok(checks), ..
+                } => {
+                    for (_, check) in checks {
+                        match check {
+                            PartCheck::Passed => summary.passed += 1,
+                            PartCheck::Mismatch { .. } | PartCheck::Failed(_) => {
+                                summary.failed += 1
+                            }
+                            PartCheck::Unrecorded(_) => summary.unrecorded += 1,
+                        }
+                    }
+                }
+            }
+        }
+
+        summary
+    }
+}
+
+/// Re-runs every recorded input for the given days and checks each part against answers.toml
+pub fn verify(solutions: &[&dyn DynSolution], answers: &AnswerFile) -> Vec<DayCheck> {
+    let mut day_checks = Vec::new();
+
+    for solution in solutions {
+        let day = solution.day();
+        let inputs = answers.inputs(day);
+        if inputs.is_empty() {
+            day_checks.push(DayCheck::Unrecorded(day));
+            continue;
+        }
+
+        for input in inputs {
+            let checks = check_input(*solution, answers, &input);
+            day_checks.push(DayCheck::Input { day, input, checks });
+        }
+    }
+
+    day_checks
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ANSWERS: &str = r#"# Day 15 part 2 is left out on purpose
+[day_5."day_5.txt"]
+part_1 = "CMZ"
+part_2 = "MCD"
+"#;
+
+    #[test]
+    fn test_expected() -> Result<()> {
+        let answers = ANSWERS.parse::<AnswerFile>()?;
+
+        assert_eq!(answers.inputs(5), vec!["day_5.txt".to_string()]);
+        assert_eq!(answers.expected(5, "day_5.txt", 1), Some("CMZ"));
+        assert_eq!(answers.expected(5, "day_5.txt", 3), None);
+        assert_eq!(answers.expected(6, "day_6.txt", 1), None);
+        assert!(answers.inputs(6).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_record() -> Result<()> {
+        let mut answers = ANSWERS.parse::<AnswerFile>()?;
+
+        answers.record(5, "day_5.txt", 2, &Answer::from("QRQ"))?;
+        answers.record(10, "day_10.txt", 1, &Answer::Int(13140))?;
+        answers.record(10, "day_10.txt", 2, &Answer::Render("#.\n.#\n".to_string()))?;
+
+        let saved = answers.doc.to_string();
+        assert!(saved.starts_with("# Day 15 part 2 is left out on purpose"));
+        assert!(!saved.contains("[day_10]\n"));
+
+        let reloaded = saved.parse::<AnswerFile>()?;
+        assert_eq!(reloaded.expected(5, "day_5.txt", 1), Some("CMZ"));
+        assert_eq!(reloaded.expected(5, "day_5.txt", 2), Some("QRQ"));
+        assert_eq!(reloaded.expected(10, "day_10.txt", 1), Some("13140"));
+        assert_eq!(reloaded.expected(10, "day_10.txt", 2), Some("#.\n.#\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_summary() {
+        let day_checks = vec![
+            DayCheck::Unrecorded(3),
+            DayCheck::Input {
+                day: 5,
+                input: "day_5.txt".to_string(),
+                checks: Ok(vec![
+                    (1, PartCheck::Passed),
+                    (2, PartCheck::Unrecorded("MCD".to_string())),
+                ]),
+            },
+            DayCheck::Input {
+                day: 6,
+                input: "day_6.txt".to_string(),
+                checks: Err(anyhow::Error::msg("Invalid input")),
+            },
+        ];
+
+        assert_eq!(
+            VerifySummary::new(&day_checks),
+            VerifySummary {
+                passed: 1,
+                failed: 1,
+                unrecorded: 2
+            }
+        );
+    }
+}
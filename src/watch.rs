@@ -0,0 +1,190 @@
+//! Re-running a day whenever its inputs change, showing what changed since the last run.
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::answer::Answer;
+use crate::input::InputSource;
+use crate::solution::DynSolution;
+
+/// Polls files for changes to their modification time or size.
+#[derive(Debug)]
+pub struct FileWatcher {
+    files: Vec<(PathBuf, Option<(SystemTime, u64)>)>,
+    polled: bool,
+}
+
+impl FileWatcher {
+    /// Watches `paths`, which don't need to exist yet. The first [`FileWatcher::poll`] always
+    /// reports a change.
+    pub fn new(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        Self {
+            files: paths.into_iter().map(|path| (path, None)).collect(),
+            polled: false,
+        }
+    }
+
+    /// Whether any file was created, changed or removed since the last poll.
+    pub fn poll(&mut self) -> bool {
+        let mut changed = !self.polled;
+        self.polled = true;
+        for (path, stamp) in &mut self.files {
+            let current = path
+                .metadata()
+                .and_then(|metadata| Ok((metadata.modified()?, metadata.len())))
+                .ok();
+            if current != *stamp {
+                changed = true;
+                *stamp = current;
+            }
+        }
+
+        changed
+    }
+}
+
+/// The real and example inputs for `day`. Built from the day number rather than the solution's
+/// input path, since some days default to their example.
+pub fn inputs(day: u32) -> [PathBuf; 2] {
+    let input_dir = Path::new(crate::verify::INPUT_DIR);
+
+    [
+        input_dir.join(format!("day_{}.txt", day)),
+        input_dir.join(format!("day_{}_test.txt", day)),
+    ]
+}
+
+/// Runs every part of `solution` against each input that exists, one line per answer.
+/// Errors are shown on a single line so they diff cleanly.
+pub fn render(solution: &dyn DynSolution, inputs: &[PathBuf]) -> String {
+    let day = solution.day();
+
+    let mut output = String::new();
+    for path in inputs.iter().filter(|path| path.exists()) {
+        let _ = writeln!(output, "== {} ==", path.display());
+
+        let day_run = match solution.run(None, &InputSource::Path(path.clone())) {
+            Ok(day_run) => day_run,
+            Err(e) => {
+                let _ = writeln!(output, "Day {} failed: {:#}", day, e);
+                continue;
+            }
+        };
+        for part_run in &day_run.parts {
+            let _ = match &part_run.answer {
+                Ok(Answer::Render(render)) => {
+                    write!(output, "Day {}-{}:\n{}", day, part_run.part, render)
+                }
+                Ok(answer) => writeln!(output, "Day {}-{}: {}", day, part_run.part, answer),
+                Err(e) => writeln!(output, "Day {}-{} failed: {:#}", day, part_run.part, e),
+            };
+        }
+    }
+
+    output
+}
+
+/// Line diff from `previous` to `current`: kept lines are indented, removed ones start with
+/// `- ` and added ones with `+ `.
+pub fn diff(previous: &str, current: &str) -> String {
+    let old = previous.lines().collect::<Vec<_>>();
+    let new = current.lines().collect::<Vec<_>>();
+
+    // Length of the longest common subsequence of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut output = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            let _ = writeln!(output, "  {}", old[i]);
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            let _ = writeln!(output, "- {}", old[i]);
+            i += 1;
+        } else {
+            let _ = writeln!(output, "+ {}", new[j]);
+            j += 1;
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let previous = "== input/day_5_test.txt ==\nDay 5-1: CMZ\nDay 5-2 failed: oops\n";
+        let current = "== input/day_5_test.txt ==\nDay 5-1: CMZ\nDay 5-2: MCD\n";
+
+        assert_eq!(
+            diff(previous, current),
+            "  == input/day_5_test.txt ==\n  Day 5-1: CMZ\n- Day 5-2 failed: oops\n+ Day 5-2: MCD\n"
+        );
+        assert_eq!(diff("", "a\n"), "+ a\n");
+        assert_eq!(diff("a\nb\n", "b\n"), "- a\n  b\n");
+    }
+
+    #[test]
+    fn test_file_watcher() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("day_5_test.txt");
+        let mut watcher = FileWatcher::new([path.clone()]);
+
+        assert!(watcher.poll());
+        assert!(!watcher.poll());
+
+        std::fs::write(&path, "move 1 from 2 to 1\n")?;
+        assert!(watcher.poll());
+        assert!(!watcher.poll());
+
+        std::fs::write(&path, "move 1 from 2 to 1\nmove 3 from 1 to 3\n")?;
+        assert!(watcher.poll());
+
+        std::fs::remove_file(&path)?;
+        assert!(watcher.poll());
+        assert!(!watcher.poll());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_inputs() {
+        assert_eq!(
+            inputs(15),
+            [
+                PathBuf::from("input/day_15.txt"),
+                PathBuf::from("input/day_15_test.txt")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let inputs = [
+            PathBuf::from("input/day_6_test.txt"),
+            PathBuf::from("input/day_6_missing.txt"),
+        ];
+
+        assert_eq!(
+            render(&crate::day_6::Day6, &inputs),
+            "== input/day_6_test.txt ==\nDay 6-1: 7\nDay 6-2: 19\n"
+        );
+    }
+}
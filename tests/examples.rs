@@ -0,0 +1,49 @@
+//! Runs every day against its `input/day_N_test.txt` example and checks each part against the
+//! example's entry in answers.toml. Adding an example and its answers is enough to cover a day.
+//! Empty examples, as left by `aoc new-day`, are skipped until filled in.
+
+use std::path::Path;
+
+use anyhow::{bail, Result};
+
+use advent_of_code_2022::registry;
+use advent_of_code_2022::verify::{self, AnswerFile, PartCheck};
+
+#[test]
+fn test_examples() -> Result<()> {
+    let answers = AnswerFile::load(verify::ANSWERS_PATH)?;
+
+    let mut failures = Vec::new();
+    for solution in registry::SOLUTIONS {
+        let day = solution.day();
+        let input = format!("day_{}_test.txt", day);
+        let path = Path::new(verify::INPUT_DIR).join(&input);
+        if !path.metadata().is_ok_and(|metadata| metadata.len() > 0) {
+            continue;
+        }
+
+        let checks = match verify::check_input(*solution, &answers, &input) {
+            Ok(checks) => checks,
+            Err(e) => {
+                failures.push(format!("Day {} ({}): FAILED {:?}", day, input, e));
+                continue;
+            }
+        };
+
+        for (part, check) in checks {
+            if !matches!(check, PartCheck::Passed) {
+                failures.push(format!("Day {}-{} ({}): {}", day, part, input, check));
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        bail!(
+            "{} example parts failed\n{}",
+            failures.len(),
+            failures.join("\n")
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,129 @@
+//! Inputs that used to panic a parser or a solver, kept as regressions next to the fuzz targets
+//! in `fuzz/`. Parsers have to come back with a [`ParseError`] pointing at the offending text,
+//! and solvers with an error.
+
+use advent_of_code_2022::parse::ParseError;
+use advent_of_code_2022::{day_1, day_11, day_15, day_18, day_7};
+
+fn located(error: ParseError) -> (usize, usize, String) {
+    (error.line, error.column, error.message)
+}
+
+#[test]
+fn test_calorie_overflow() {
+    let error = day_1::build_elf_list("1000\n\n18446744073709551615\n1\n")
+        .err()
+        .map(located);
+
+    assert_eq!(error, Some((4, 1, "Calorie total too large".to_string())));
+}
+
+#[test]
+fn test_file_size_overflow() {
+    let input = "$ cd /\n$ ls\n18446744073709551615 a.txt\n$ cd b\n$ ls\n1 c.txt\n";
+    let error = day_7::build_fs_tree(input).err().map(located);
+
+    assert_eq!(error, Some((6, 1, "Total file size too large".to_string())));
+}
+
+#[test]
+fn test_sensor_position_range() {
+    let input = "Sensor at x=-9223372036854775808, y=0: closest beacon is at x=0, y=0\n";
+    let error = day_15::parse(input).err().map(located);
+
+    assert_eq!(error, Some((1, 13, "Invalid x position".to_string())));
+}
+
+#[test]
+fn test_cube_position_range() {
+    let error = day_18::parse("1,1,1\n9223372036854775807,0,0\n")
+        .err()
+        .map(located);
+
+    assert_eq!(error, Some((2, 1, "Invalid x position".to_string())));
+}
+
+#[test]
+fn test_disk_space() {
+    let error = |input: &str| day_7::day_7_2(&day_7::build_fs_tree(input).unwrap()).unwrap_err();
+
+    let full = error("$ cd /\n$ ls\n80000000 a.txt\n");
+    assert_eq!(
+        full.to_string(),
+        "Files take up 80000000, more than the disk's 70000000"
+    );
+
+    let empty = error("$ cd /\n$ ls\n8 a.txt\n");
+    assert_eq!(
+        empty.to_string(),
+        "69999992 is already free, nothing needs deleting"
+    );
+}
+
+#[test]
+fn test_monkey_business_monkeys() {
+    let one_monkey = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 0
+    If false: throw to monkey 0
+";
+
+    for input in ["", one_monkey] {
+        let monkes = day_11::parse(input).unwrap();
+        let error = day_11::day_11_1(&monkes).unwrap_err();
+
+        assert!(
+            error
+                .to_string()
+                .starts_with("Monkey business needs two monkeys"),
+            "{}",
+            error
+        );
+    }
+}
+
+#[test]
+fn test_monkey_worry_overflow() {
+    let input = "Monkey 0:
+  Starting items: 5000000000
+  Operation: new = old * old
+  Test: divisible by 7
+    If true: throw to monkey 1
+    If false: throw to monkey 1
+
+Monkey 1:
+  Starting items: 1
+  Operation: new = old + 1
+  Test: divisible by 3
+    If true: throw to monkey 0
+    If false: throw to monkey 0
+";
+    let monkes = day_11::parse(input).unwrap();
+    let error = day_11::day_11_1(&monkes).unwrap_err();
+
+    assert!(
+        error.to_string().starts_with("Worry level overflowed"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn test_monkey_divisor_overflow() {
+    let monkey = |idx: usize| {
+        format!(
+            "Monkey {}:\n  Starting items: 1\n  Operation: new = old + 1\n  \
+             Test: divisible by 4294967311\n    If true: throw to monkey {}\n    \
+             If false: throw to monkey {}\n",
+            idx,
+            1 - idx,
+            1 - idx
+        )
+    };
+    let monkes = day_11::parse(&format!("{}\n{}", monkey(0), monkey(1))).unwrap();
+    let error = day_11::day_11_2(&monkes).unwrap_err();
+
+    assert_eq!(error.to_string(), "Monkey divisors multiply past u64");
+}
@@ -0,0 +1,341 @@
+//! Property tests: random small inputs, run through the real parser and solver, checked against
+//! naive reference solutions.
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use proptest::collection::vec;
+use proptest::option;
+use proptest::prelude::*;
+
+use advent_of_code_2022::answer::Answer;
+use advent_of_code_2022::{day_12, day_13, day_15, day_18, day_6, day_8};
+
+fn marker_reference(code: &str, count: usize) -> Option<usize> {
+    if count == 0 {
+        return None;
+    }
+    let chars = code.chars().collect::<Vec<_>>();
+
+    (count..=chars.len())
+        .find(|&end| chars[end - count..end].iter().collect::<HashSet<_>>().len() == count)
+}
+
+proptest! {
+    #[test]
+    fn test_day_6_marker(code in "[a-hé€🦀]{0,30}", count in 0usize..=6) {
+        prop_assert_eq!(
+            day_6::find_unique_seq(&code, count).ok(),
+            marker_reference(&code, count)
+        );
+    }
+}
+
+fn trees() -> impl Strategy<Value = Vec<Vec<u32>>> {
+    (1usize..=8, 1usize..=8).prop_flat_map(|(width, height)| vec(vec(0u32..10, width), height))
+}
+
+/// Heights seen walking from `(x, y)` to the edge in each direction, nearest first.
+fn walks(trees: &[Vec<u32>], x: usize, y: usize) -> [Vec<u32>; 4] {
+    [
+        (0..y).rev().map(|y| trees[y][x]).collect(),
+        (y + 1..trees.len()).map(|y| trees[y][x]).collect(),
+        (0..x).rev().map(|x| trees[y][x]).collect(),
+        (x + 1..trees[0].len()).map(|x| trees[y][x]).collect(),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn test_day_8_trees(trees in trees()) {
+        let input = trees
+            .iter()
+            .map(|row| row.iter().map(u32::to_string).collect::<String>() + "\n")
+            .collect::<String>();
+        let grid = day_8::parse(&input)?;
+
+        let mut visible = 0;
+        let mut scenic_score = 0;
+        for y in 0..trees.len() {
+            for x in 0..trees[0].len() {
+                let height = trees[y][x];
+                let walks = walks(&trees, x, y);
+
+                if walks.iter().any(|walk| walk.iter().all(|h| *h < height)) {
+                    visible += 1;
+                }
+
+                let score = walks
+                    .iter()
+                    .map(|walk| {
+                        let mut seen = 0;
+                        for h in walk {
+                            seen += 1;
+                            if *h >= height {
+                                break;
+                            }
+                        }
+                        seen
+                    })
+                    .product::<usize>();
+                scenic_score = scenic_score.max(score);
+            }
+        }
+
+        prop_assert_eq!(day_8::day_8_1(&grid).ok(), Some(Answer::from(visible as u64)));
+        prop_assert_eq!(day_8::day_8_2(&grid).ok(), Some(Answer::from(scenic_score)));
+    }
+}
+
+/// Heightmaps with `S` in the top left corner and heights mostly falling with the distance from
+/// `E`, so there's a climb to it from far enough away. A few squares get a random height instead.
+fn topo_map() -> impl Strategy<Value = Vec<Vec<char>>> {
+    (1usize..=16, 2usize..=16)
+        .prop_flat_map(|(width, height)| {
+            let cells = width * height;
+            (
+                Just(width),
+                1..cells,
+                vec(0usize..=1, cells),
+                vec(option::weighted(0.1, 0u8..26), cells),
+            )
+        })
+        .prop_map(|(width, end, dips, random)| {
+            let squares = (0..dips.len())
+                .map(|idx| match idx {
+                    0 => 'S',
+                    idx if idx == end => 'E',
+                    idx => {
+                        let to_end = (idx % width).abs_diff(end % width)
+                            + (idx / width).abs_diff(end / width);
+                        let rising = 26usize.saturating_sub(to_end + dips[idx]);
+                        char::from(b'a' + random[idx].unwrap_or(rising as u8))
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            squares.chunks(width).map(<[char]>::to_vec).collect()
+        })
+}
+
+/// Relaxes every move until no distance improves, starting from `S` and optionally every `a`.
+fn shortest_path_reference(squares: &[Vec<char>], include_a: bool) -> Option<u32> {
+    let height = |square: char| match square {
+        'S' => 'a' as u32,
+        'E' => 'z' as u32,
+        square => square as u32,
+    };
+    let (width, depth) = (squares[0].len(), squares.len());
+
+    let mut dist = vec![vec![None::<u32>; width]; depth];
+    for (y, row) in squares.iter().enumerate() {
+        for (x, square) in row.iter().enumerate() {
+            if *square == 'S' || (include_a && *square == 'a') {
+                dist[y][x] = Some(0);
+            }
+        }
+    }
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for y in 0..depth {
+            for x in 0..width {
+                let Some(steps) = dist[y][x] else {
+                    continue;
+                };
+
+                let moves = [
+                    (x + 1, y),
+                    (x.wrapping_sub(1), y),
+                    (x, y + 1),
+                    (x, y.wrapping_sub(1)),
+                ];
+                for (nx, ny) in moves {
+                    if nx >= width || ny >= depth {
+                        continue;
+                    }
+                    if height(squares[ny][nx]) > height(squares[y][x]) + 1 {
+                        continue;
+                    }
+                    if dist[ny][nx].is_none_or(|old| steps + 1 < old) {
+                        dist[ny][nx] = Some(steps + 1);
+                        changed = true;
+                    }
+                }
+            }
+        }
+    }
+
+    squares
+        .iter()
+        .flatten()
+        .zip(dist.iter().flatten())
+        .find(|(square, _)| **square == 'E')
+        .and_then(|(_, dist)| *dist)
+}
+
+proptest! {
+    #[test]
+    fn test_day_12_shortest_path(squares in topo_map()) {
+        let input = squares
+            .iter()
+            .map(|row| row.iter().collect::<String>() + "\n")
+            .collect::<String>();
+        let topo_map = day_12::TopoMap::try_from(input.as_str())?;
+
+        for include_a in [false, true] {
+            prop_assert_eq!(
+                topo_map.find_shortest_path(include_a).ok(),
+                shortest_path_reference(&squares, include_a)
+            );
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Packet {
+    Int(u32),
+    List(Vec<Packet>),
+}
+
+impl std::fmt::Display for Packet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Packet::Int(val) => write!(f, "{}", val),
+            Packet::List(items) => {
+                let items = items.iter().map(Packet::to_string).collect::<Vec<_>>();
+                write!(f, "[{}]", items.join(","))
+            }
+        }
+    }
+}
+
+fn packet() -> impl Strategy<Value = Packet> {
+    let value = (0u32..=10)
+        .prop_map(Packet::Int)
+        .prop_recursive(3, 16, 4, |inner| vec(inner, 0..4).prop_map(Packet::List));
+
+    vec(value, 0..4).prop_map(Packet::List)
+}
+
+fn compare_reference(left: &Packet, right: &Packet) -> Ordering {
+    match (left, right) {
+        (Packet::Int(a), Packet::Int(b)) => a.cmp(b),
+        (Packet::Int(_), Packet::List(_)) => {
+            compare_reference(&Packet::List(vec![left.clone()]), right)
+        }
+        (Packet::List(_), Packet::Int(_)) => {
+            compare_reference(left, &Packet::List(vec![right.clone()]))
+        }
+        (Packet::List(a), Packet::List(b)) => {
+            for (a, b) in a.iter().zip(b) {
+                match compare_reference(a, b) {
+                    Ordering::Equal => continue,
+                    ordering => return ordering,
+                }
+            }
+
+            a.len().cmp(&b.len())
+        }
+    }
+}
+
+/// Sorts every packet along with the dividers, which go first among packets comparing equal.
+fn decoder_key_reference(pairs: &[(Packet, Packet)]) -> usize {
+    let divider = |val| Packet::List(vec![Packet::List(vec![Packet::Int(val)])]);
+
+    let mut packets = vec![(true, divider(2)), (true, divider(6))];
+    packets.extend(
+        pairs
+            .iter()
+            .flat_map(|(a, b)| [(false, a.clone()), (false, b.clone())]),
+    );
+    packets.sort_by(|(_, a), (_, b)| compare_reference(a, b));
+
+    packets
+        .iter()
+        .enumerate()
+        .filter(|(_, (is_divider, _))| *is_divider)
+        .map(|(idx, _)| idx + 1)
+        .product()
+}
+
+proptest! {
+    #[test]
+    fn test_day_13_ordering(pairs in vec((packet(), packet()), 1..8)) {
+        let input = pairs
+            .iter()
+            .map(|(left, right)| format!("{}\n{}\n", left, right))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let parsed = day_13::parse(&input)?;
+
+        let ordered = pairs
+            .iter()
+            .enumerate()
+            .filter(|(_, (left, right))| compare_reference(left, right) == Ordering::Less)
+            .map(|(idx, _)| idx + 1)
+            .sum::<usize>();
+
+        prop_assert_eq!(day_13::sum_of_ordered_pairs(&parsed), ordered);
+        prop_assert_eq!(day_13::decoder_key(&parsed), decoder_key_reference(&pairs));
+    }
+}
+
+proptest! {
+    #[test]
+    fn test_day_15_row_coverage(
+        reports in vec((-10i64..=10, -10i64..=10, -10i64..=10, -10i64..=10), 1..6),
+        row in -15i64..=15,
+    ) {
+        let input = reports
+            .iter()
+            .map(|(sx, sy, bx, by)| {
+                format!(
+                    "Sensor at x={}, y={}: closest beacon is at x={}, y={}\n",
+                    sx, sy, bx, by
+                )
+            })
+            .collect::<String>();
+        let pairs = day_15::parse(&input)?;
+
+        // Sensors reach at most 40 squares with these coordinates
+        let covered = (-60..=60)
+            .filter(|&x| {
+                let is_beacon = reports.iter().any(|&(_, _, bx, by)| (bx, by) == (x, row));
+                let in_range = reports.iter().any(|&(sx, sy, bx, by)| {
+                    (sx - x).abs() + (sy - row).abs() <= (sx - bx).abs() + (sy - by).abs()
+                });
+
+                in_range && !is_beacon
+            })
+            .count();
+
+        prop_assert_eq!(day_15::check_at_y(&pairs, row), covered as u64);
+    }
+}
+
+proptest! {
+    #[test]
+    fn test_day_18_surface_area(cubes in vec((0i64..6, 0i64..6, 0i64..6), 0..40)) {
+        let input = cubes
+            .iter()
+            .map(|(x, y, z)| format!("{},{},{}\n", x, y, z))
+            .collect::<String>();
+        let lava_flow = day_18::parse(&input)?;
+
+        // Every pair of cubes sharing a face hides one face of each
+        let cubes = cubes.into_iter().collect::<HashSet<_>>().into_iter().collect::<Vec<_>>();
+        let mut touching = 0;
+        for (idx, a) in cubes.iter().enumerate() {
+            for b in &cubes[idx + 1..] {
+                if (a.0 - b.0).abs() + (a.1 - b.1).abs() + (a.2 - b.2).abs() == 1 {
+                    touching += 1;
+                }
+            }
+        }
+
+        prop_assert_eq!(lava_flow.calculate_surface_area(), 6 * cubes.len() - 2 * touching);
+    }
+}
@@ -0,0 +1,212 @@
+//! `fetch` and `submit` against a local stub server standing in for the site.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use anyhow::Result;
+use tiny_http::{Header, Response, Server};
+
+use advent_of_code_2022::config::Config;
+use advent_of_code_2022::fetch::{fetch_input, FetchOutcome};
+use advent_of_code_2022::http::{CachedClient, UreqClient};
+use advent_of_code_2022::submit::{submit, SubmissionLog, Verdict};
+
+const RIGHT: &str = "<main><article><p>That's the right answer! You are one gold star closer to \
+    collecting enough star fruit.</p></article></main>";
+const WRONG: &str = "<main><article><p>That's not the right answer. Please wait one minute \
+    before trying again.</p></article></main>";
+
+/// Serves day 5's input and judges its answers for requests carrying the `abc` session cookie.
+/// The `xyz` session gets a different input. Counts every request.
+fn stub_server() -> (String, Arc<AtomicUsize>) {
+    let server = Server::http("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", server.server_addr().to_ip().unwrap());
+    let hits = Arc::new(AtomicUsize::new(0));
+
+    let counter = hits.clone();
+    thread::spawn(move || {
+        for mut request in server.incoming_requests() {
+            counter.fetch_add(1, Ordering::SeqCst);
+
+            let mut form = String::new();
+            request.as_reader().read_to_string(&mut form).unwrap();
+
+            let session = request
+                .headers()
+                .iter()
+                .find(|header| header.field.equiv("Cookie"))
+                .map(|header| header.value.as_str().to_string());
+            let logged_in = matches!(session.as_deref(), Some("session=abc" | "session=xyz"));
+            let response = match (logged_in, request.url()) {
+                (false, _) => {
+                    Response::from_string("Puzzle inputs differ by user.").with_status_code(400)
+                }
+                (true, "/2022/day/5/input") if session.as_deref() == Some("session=xyz") => {
+                    Response::from_string("move 2 from 1 to 3\n")
+                }
+                (true, "/2022/day/5/input") => Response::from_string("move 1 from 2 to 1\n"),
+                (true, "/2022/day/5/answer") => match form.as_str() {
+                    "level=1&answer=CMZ" | "level=2&answer=MCD" => Response::from_string(RIGHT),
+                    _ => Response::from_string(WRONG),
+                },
+                (true, _) => Response::from_string("404 Not Found").with_status_code(404),
+            };
+
+            let text = Header::from_bytes("Content-Type", "text/plain").unwrap();
+            request.respond(response.with_header(text)).unwrap();
+        }
+    });
+
+    (base_url, hits)
+}
+
+#[test]
+fn test_fetch_caches_input() -> Result<()> {
+    let (base_url, hits) = stub_server();
+    let dir = tempfile::tempdir()?;
+    let config = Config {
+        session: Some("abc".to_string()),
+        base_url,
+        cache_dir: dir.path().join("cache"),
+    };
+    let client = CachedClient::new(
+        UreqClient::new(config.session.clone()),
+        &config.cache_dir,
+        config.session.as_deref(),
+    );
+    let path = dir.path().join("input/day_5.txt");
+
+    assert_eq!(
+        fetch_input(&client, &config, 5, &path)?,
+        FetchOutcome::Written
+    );
+    assert_eq!(std::fs::read_to_string(&path)?, "move 1 from 2 to 1\n");
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+    // An existing input is left alone
+    std::fs::write(&path, "edited")?;
+    assert_eq!(
+        fetch_input(&client, &config, 5, &path)?,
+        FetchOutcome::Exists
+    );
+    assert_eq!(std::fs::read_to_string(&path)?, "edited");
+
+    // An empty stub is filled in from the cache without another request
+    std::fs::write(&path, "")?;
+    assert_eq!(
+        fetch_input(&client, &config, 5, &path)?,
+        FetchOutcome::Written
+    );
+    assert_eq!(std::fs::read_to_string(&path)?, "move 1 from 2 to 1\n");
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_fetch_cache_per_session() -> Result<()> {
+    let (base_url, hits) = stub_server();
+    let dir = tempfile::tempdir()?;
+    let mut config = Config {
+        session: Some("abc".to_string()),
+        base_url,
+        cache_dir: dir.path().join("cache"),
+    };
+    let path = dir.path().join("input/day_5.txt");
+
+    for (session, input) in [
+        ("abc", "move 1 from 2 to 1\n"),
+        ("xyz", "move 2 from 1 to 3\n"),
+        ("abc", "move 1 from 2 to 1\n"),
+    ] {
+        config.session = Some(session.to_string());
+        let client = CachedClient::new(
+            UreqClient::new(config.session.clone()),
+            &config.cache_dir,
+            config.session.as_deref(),
+        );
+
+        fetch_input(&client, &config, 5, &path)?;
+        assert_eq!(
+            std::fs::read_to_string(&path)?,
+            input,
+            "session {}",
+            session
+        );
+        std::fs::write(&path, "")?;
+    }
+
+    // Switching back to the first session is served from its own cache
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_fetch_errors() -> Result<()> {
+    let (base_url, hits) = stub_server();
+    let dir = tempfile::tempdir()?;
+    let config = Config {
+        session: Some("wrong".to_string()),
+        base_url,
+        cache_dir: dir.path().join("cache"),
+    };
+    let client = CachedClient::new(
+        UreqClient::new(config.session.clone()),
+        &config.cache_dir,
+        config.session.as_deref(),
+    );
+    let path = dir.path().join("input/day_5.txt");
+
+    let error = fetch_input(&client, &config, 5, &path).unwrap_err();
+    assert!(error.to_string().contains("status 400"), "{}", error);
+    assert!(!path.exists());
+
+    // Failures aren't cached
+    assert!(fetch_input(&client, &config, 5, &path).is_err());
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+    let client = UreqClient::new(None);
+    let error = fetch_input(&client, &config, 5, &path).unwrap_err();
+    assert!(error.to_string().contains("No session token"), "{}", error);
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_submit() -> Result<()> {
+    let (base_url, hits) = stub_server();
+    let dir = tempfile::tempdir()?;
+    let config = Config {
+        session: Some("abc".to_string()),
+        base_url,
+        cache_dir: dir.path().join("cache"),
+    };
+    let client = UreqClient::new(config.session.clone());
+    let log_path = dir.path().join("submissions.jsonl");
+    let mut log = SubmissionLog::load(&log_path)?;
+
+    let submission = submit(&client, &config, &mut log, 5, 1, "CMZ")?;
+    assert_eq!(submission.verdict, Verdict::Correct);
+    assert!(submission.response.starts_with("That's the right answer!"));
+
+    let submission = submit(&client, &config, &mut log, 5, 2, "ABC")?;
+    assert_eq!(submission.verdict, Verdict::Incorrect);
+    assert_eq!(submission.wait, Some(60));
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+    // Refused locally: solved, already rejected, and inside the requested wait
+    let mut log = SubmissionLog::load(&log_path)?;
+    assert_eq!(log.entries().len(), 2);
+    let error = submit(&client, &config, &mut log, 5, 1, "CMZ").unwrap_err();
+    assert!(error.to_string().contains("already solved"), "{}", error);
+    let error = submit(&client, &config, &mut log, 5, 2, "ABC").unwrap_err();
+    assert!(error.to_string().contains("already rejected"), "{}", error);
+    let error = submit(&client, &config, &mut log, 5, 2, "MCD").unwrap_err();
+    assert!(error.to_string().contains("asked to wait"), "{}", error);
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+    Ok(())
+}